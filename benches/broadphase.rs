@@ -0,0 +1,82 @@
+//! Broadphase scaling: brute-force pair testing (what the hit systems used to do)
+//! against the uniform-grid `SpatialHash` used by the collision system.
+//!
+//! Run with `cargo bench --bench broadphase`.
+
+use bevy::{prelude::*, sprite::collide_aabb::collide};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+#[path = "../src/collision/spatial_hash.rs"]
+#[allow(dead_code)]
+mod spatial_hash;
+
+use spatial_hash::SpatialHash;
+
+// same playfield and cell size as the game
+const PLAYFIELD: Vec2 = Vec2::new(598., 676.);
+const CELL_SIZE: f32 = 64.;
+
+fn colliders(count: usize) -> Vec<(Entity, Vec2, Vec2)> {
+    let mut rng = StdRng::seed_from_u64(42);
+    (0..count)
+        .map(|i| {
+            let center = Vec2::new(
+                rng.gen_range(-PLAYFIELD.x / 2.0..PLAYFIELD.x / 2.),
+                rng.gen_range(-PLAYFIELD.y / 2.0..PLAYFIELD.y / 2.),
+            );
+            // mix of laser sized and ship sized colliders (already scaled)
+            let size = if i % 4 == 0 { Vec2::new(72., 37.) } else { Vec2::new(5., 27.) };
+            (Entity::from_raw(i as u32), center, size)
+        })
+        .collect()
+}
+
+fn brute_force(colliders: &[(Entity, Vec2, Vec2)]) -> usize {
+    let mut hits = 0;
+    for (i, (_, a_pos, a_size)) in colliders.iter().enumerate() {
+        for (_, b_pos, b_size) in &colliders[i + 1..] {
+            if collide(a_pos.extend(0.), *a_size, b_pos.extend(0.), *b_size).is_some() {
+                hits += 1;
+            }
+        }
+    }
+    hits
+}
+
+fn spatial_hash(hash: &mut SpatialHash, colliders: &[(Entity, Vec2, Vec2)]) -> usize {
+    hash.clear();
+    for (entity, pos, size) in colliders {
+        hash.insert(*entity, *pos, *size);
+    }
+    hash.candidate_pairs()
+        .into_iter()
+        .filter(|(a, b)| {
+            let (_, a_pos, a_size) = colliders[a.index() as usize];
+            let (_, b_pos, b_size) = colliders[b.index() as usize];
+            collide(a_pos.extend(0.), a_size, b_pos.extend(0.), b_size).is_some()
+        })
+        .count()
+}
+
+fn broadphase(c: &mut Criterion) {
+    let mut group = c.benchmark_group("broadphase");
+    for count in [10, 100, 500, 2_000] {
+        let colliders = colliders(count);
+        let mut hash = SpatialHash::new(CELL_SIZE);
+
+        // both must find the same collisions
+        assert_eq!(brute_force(&colliders), spatial_hash(&mut hash, &colliders));
+
+        group.bench_with_input(BenchmarkId::new("brute_force", count), &colliders, |b, colliders| {
+            b.iter(|| brute_force(colliders))
+        });
+        group.bench_with_input(BenchmarkId::new("spatial_hash", count), &colliders, |b, colliders| {
+            b.iter(|| spatial_hash(&mut hash, colliders))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, broadphase);
+criterion_main!(benches);
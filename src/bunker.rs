@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+
+use crate::{BUNKER_CELL_HEALTH, BUNKER_CELL_SIZE, BUNKER_COUNT, BUNKER_HEIGHT, components::{BunkerCell, Health, SpriteSize}, collision::{Collider, Layer}, damage::{DamageLabel, DeathEvent}, enemy::wave::WaveStartedEvent, player::player_spawn_y, playfield::Playfield, state::{GameState, despawn_with}};
+
+const BUNKER_COLOR: Color = Color::rgb(0.2, 0.9, 0.3);
+
+/// Cells of one bunker, top row first (`#` is a cell) - the classic arch.
+pub const BUNKER_SHAPE: [&str; 6] = [
+    "..######..",
+    ".########.",
+    "##########",
+    "##########",
+    "###....###",
+    "##......##",
+];
+
+pub struct BunkerPlugin;
+
+impl Plugin for BunkerPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(bunker_rebuild_system)
+                    .with_system(bunker_erosion_system.after(DamageLabel::Apply))
+                    .with_system(bunker_cell_death_system.after(DamageLabel::Apply))
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::Playing)
+                    .with_system(despawn_with::<BunkerCell>)
+            );
+    }
+}
+
+/// Fresh bunkers at the start of every wave, whatever was left of the previous ones.
+fn bunker_rebuild_system(
+    mut commands: Commands,
+    mut wave_events: EventReader<WaveStartedEvent>,
+    playfield: Res<Playfield>,
+    query: Query<Entity, With<BunkerCell>>,
+) {
+    let Some(wave) = wave_events.iter().last() else {
+        return;
+    };
+    info!("rebuild bunkers for wave {}", wave.number);
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    // evenly spread, between the player and the enemies
+    let y = player_spawn_y(&playfield) + BUNKER_HEIGHT;
+    for i in 0..BUNKER_COUNT {
+        let x = -playfield.w / 2. + playfield.w * (i as f32 + 0.5) / BUNKER_COUNT as f32;
+        spawn_bunker(&mut commands, Vec2::new(x, y));
+    }
+}
+
+fn spawn_bunker(commands: &mut Commands, center: Vec2) {
+    let (cols, rows) = (BUNKER_SHAPE[0].len(), BUNKER_SHAPE.len());
+    let top_left = center + Vec2::new(-(cols as f32 - 1.) / 2., (rows as f32 - 1.) / 2.) * BUNKER_CELL_SIZE;
+
+    for (row, line) in BUNKER_SHAPE.iter().enumerate() {
+        for (col, _) in line.chars().enumerate().filter(|(_, c)| *c == '#') {
+            let position = top_left + Vec2::new(col as f32, -(row as f32)) * BUNKER_CELL_SIZE;
+            commands
+                .spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: BUNKER_COLOR,
+                        custom_size: Some(Vec2::splat(BUNKER_CELL_SIZE)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(position.extend(5.)),
+                    ..default()
+                })
+                .insert(BunkerCell)
+                .insert(SpriteSize::from((BUNKER_CELL_SIZE, BUNKER_CELL_SIZE)))
+                .insert(Health::new(BUNKER_CELL_HEALTH))
+                .insert(Collider::new(Layer::Bunker, &[]));
+        }
+    }
+}
+
+/// Damaged cells fade out, rather than the red tint of damaged ships.
+#[allow(clippy::type_complexity)]
+fn bunker_erosion_system(mut query: Query<(&Health, &mut Sprite), (With<BunkerCell>, Changed<Health>)>) {
+    for (health, mut sprite) in query.iter_mut() {
+        let mut color = BUNKER_COLOR;
+        color.set_a(health.current as f32 / health.max as f32);
+        sprite.color = color;
+    }
+}
+
+fn bunker_cell_death_system(
+    mut commands: Commands,
+    mut death_events: EventReader<DeathEvent>,
+    query: Query<(), With<BunkerCell>>,
+) {
+    for death in death_events.iter() {
+        if query.get(death.entity).is_ok() {
+            commands.entity(death.entity).despawn();
+        }
+    }
+}
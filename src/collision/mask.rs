@@ -0,0 +1,124 @@
+use bevy::{prelude::*, asset::HandleId, render::render_resource::TextureFormat, utils::HashMap};
+
+/// Texels with at least this alpha are solid.
+const ALPHA_THRESHOLD: u8 = 128;
+
+// region: --- Alpha Mask
+
+/// One bit per texel, set where the image is opaque. Row 0 is the top of the image.
+pub struct AlphaMask {
+    width: i32,
+    height: i32,
+    bits: Vec<u64>,
+}
+
+impl AlphaMask {
+    /// Built from the alpha channel, only 8 bit RGBA images (what PNGs load as) are supported.
+    pub fn from_image(image: &Image) -> Option<Self> {
+        if !matches!(image.texture_descriptor.format, TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb) {
+            return None;
+        }
+        let size = image.texture_descriptor.size;
+        let (width, height) = (size.width as usize, size.height as usize);
+
+        let mut bits = vec![0u64; (width * height).div_ceil(64)];
+        for (i, texel) in image.data.chunks_exact(4).take(width * height).enumerate() {
+            if texel[3] >= ALPHA_THRESHOLD {
+                bits[i / 64] |= 1 << (i % 64);
+            }
+        }
+
+        Some(Self {
+            width: width as i32,
+            height: height as i32,
+            bits,
+        })
+    }
+
+    /// Outside of the image is transparent.
+    pub fn is_opaque(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return false;
+        }
+        let i = (y * self.width + x) as usize;
+        self.bits[i / 64] & (1 << (i % 64)) != 0
+    }
+}
+
+/// Alpha masks of the loaded images, kept in sync by `alpha_mask_system`.
+#[derive(Resource, Default)]
+pub struct AlphaMasks(HashMap<HandleId, AlphaMask>);
+
+impl AlphaMasks {
+    pub fn get(&self, handle: &Handle<Image>) -> Option<&AlphaMask> {
+        self.0.get(&handle.id())
+    }
+}
+
+// endregion: --- Alpha Mask
+
+// region: --- Mask Test
+
+/// A mask placed in the world by its sprite's transform (sprite drawn at the image's size).
+pub struct PlacedMask<'a> {
+    mask: &'a AlphaMask,
+    world_to_local: Mat4,
+}
+
+impl<'a> PlacedMask<'a> {
+    pub fn new(mask: &'a AlphaMask, tf: &Transform) -> Self {
+        Self {
+            mask,
+            world_to_local: tf.compute_matrix().inverse(),
+        }
+    }
+
+    fn contains(&self, point: Vec2) -> bool {
+        // local space is centered on the sprite, y up - texel space starts top left, y down
+        let local = self.world_to_local.transform_point3(point.extend(0.));
+        let x = (local.x + self.mask.width as f32 / 2.).floor() as i32;
+        let y = (self.mask.height as f32 / 2. - local.y).floor() as i32;
+        self.mask.is_opaque(x, y)
+    }
+}
+
+/// Whether some point of the `min..max` world rect is opaque in all `masks`,
+/// sampled every `step` world units.
+pub fn masks_overlap(masks: &[PlacedMask], min: Vec2, max: Vec2, step: f32) -> bool {
+    let mut y = min.y + step / 2.;
+    while y < max.y {
+        let mut x = min.x + step / 2.;
+        while x < max.x {
+            let point = Vec2::new(x, y);
+            if masks.iter().all(|mask| mask.contains(point)) {
+                return true;
+            }
+            x += step;
+        }
+        y += step;
+    }
+    false
+}
+
+// endregion: --- Mask Test
+
+/// Build the mask of every image as it (re)loads.
+pub fn alpha_mask_system(
+    mut events: EventReader<AssetEvent<Image>>,
+    images: Res<Assets<Image>>,
+    mut masks: ResMut<AlphaMasks>,
+) {
+    for event in events.iter() {
+        match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+                match images.get(handle).and_then(AlphaMask::from_image) {
+                    Some(mask) => masks.0.insert(handle.id(), mask),
+                    None => masks.0.remove(&handle.id()),
+                };
+            }
+            AssetEvent::Removed { handle } => {
+                masks.0.remove(&handle.id());
+            }
+        }
+    }
+}
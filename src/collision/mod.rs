@@ -0,0 +1,170 @@
+use bevy::{prelude::*, sprite::collide_aabb::collide};
+
+use crate::{components::SpriteSize, state::GameState, COLLISION_CELL_SIZE};
+
+pub use self::mask::{AlphaMask, AlphaMasks, PlacedMask, masks_overlap};
+
+use self::{spatial_hash::SpatialHash, mask::alpha_mask_system};
+
+mod spatial_hash;
+mod mask;
+
+// region: --- Collision Layers
+
+/// What an entity is, for collision purposes. Each layer is one bit of a `Collider` mask.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layer {
+    Player = 1 << 0,
+    Enemy = 1 << 1,
+    PlayerLaser = 1 << 2,
+    EnemyLaser = 1 << 3,
+    Bunker = 1 << 4,
+    PowerUp = 1 << 5,
+}
+
+impl Layer {
+    pub fn bit(self) -> u32 {
+        self as u32
+    }
+}
+
+/// Collision shape is the entity's `SpriteSize` scaled by its `Transform` (refined by `PixelPerfect`).
+/// Only pairs where one collider's mask includes the other's layer are tested.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Collider {
+    pub layer: Layer,
+    pub mask: u32, // layers this collider reports collisions with
+}
+
+impl Collider {
+    pub fn new(layer: Layer, collides_with: &[Layer]) -> Self {
+        Self {
+            layer,
+            mask: collides_with.iter().fold(0, |mask, layer| mask | layer.bit()),
+        }
+    }
+
+    pub fn collides_with(&self, other: &Collider) -> bool {
+        self.mask & other.layer.bit() != 0
+    }
+}
+
+/// Refine this entity's collisions with the alpha mask of its sprite image,
+/// so that the transparent parts of its bounding box don't collide.
+/// Falls back to the bounding box while the mask is not available.
+#[derive(Component)]
+pub struct PixelPerfect;
+
+// endregion: --- Collision Layers
+
+/// Sent for `entity` when it overlaps `other` and its mask includes `other`'s layer.
+/// If both masks include each other, one event is sent per side.
+#[derive(Clone, Copy, Debug)]
+pub struct CollisionEvent {
+    pub entity: Entity,
+    pub layer: Layer,
+    pub other: Entity,
+    pub other_layer: Layer,
+}
+
+#[derive(SystemLabel, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CollisionLabel;
+
+pub struct CollisionPlugin;
+
+impl Plugin for CollisionPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<CollisionEvent>()
+            .insert_resource(SpatialHash::new(COLLISION_CELL_SIZE))
+            .init_resource::<AlphaMasks>()
+            .add_system(alpha_mask_system)
+            .add_system_set(
+                SystemSet::on_enter(GameState::Playing)
+                    .with_system(spatial_hash_reset_system)
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(spatial_hash_system)
+                    .with_system(collision_system.label(CollisionLabel).after(spatial_hash_system))
+            );
+    }
+}
+
+/// Start every game from an empty broadphase: `clear` keeps the cells of earlier games,
+/// and with them an iteration order that would depend on those games.
+fn spatial_hash_reset_system(mut spatial_hash: ResMut<SpatialHash>) {
+    *spatial_hash = SpatialHash::new(COLLISION_CELL_SIZE);
+}
+
+/// Rebuild the broadphase from this frame's collider positions.
+fn spatial_hash_system(
+    mut spatial_hash: ResMut<SpatialHash>,
+    query: Query<(Entity, &Transform, &SpriteSize), With<Collider>>,
+) {
+    spatial_hash.clear();
+    for (entity, tf, size) in query.iter() {
+        spatial_hash.insert(entity, tf.translation.truncate(), size.0 * tf.scale.truncate());
+    }
+}
+
+fn collision_system(
+    mut collision_events: EventWriter<CollisionEvent>,
+    spatial_hash: Res<SpatialHash>,
+    alpha_masks: Res<AlphaMasks>,
+    query: Query<(&Transform, &SpriteSize, &Collider)>,
+    mask_query: Query<&Handle<Image>, With<PixelPerfect>>,
+) {
+    for (entity, other) in spatial_hash.candidate_pairs() {
+        let (Ok((tf, size, collider)), Ok((other_tf, other_size, other_collider))) = (query.get(entity), query.get(other)) else {
+            continue;
+        };
+        let forward = collider.collides_with(other_collider);
+        let backward = other_collider.collides_with(collider);
+        if !forward && !backward {
+            continue;
+        }
+
+        let (size, other_size) = (size.0 * tf.scale.truncate(), other_size.0 * other_tf.scale.truncate());
+        let collision = collide(tf.translation, size, other_tf.translation, other_size);
+        if collision.is_none() {
+            continue;
+        }
+
+        // narrow down to the opaque texels, for the pixel perfect side(s)
+        let masks: Vec<PlacedMask> = [(entity, tf), (other, other_tf)]
+            .into_iter()
+            .filter_map(|(entity, tf)| {
+                let mask = alpha_masks.get(mask_query.get(entity).ok()?)?;
+                Some(PlacedMask::new(mask, tf))
+            })
+            .collect();
+        if !masks.is_empty() {
+            let (center, other_center) = (tf.translation.truncate(), other_tf.translation.truncate());
+            let min = (center - size / 2.).max(other_center - other_size / 2.);
+            let max = (center + size / 2.).min(other_center + other_size / 2.);
+            // one sample per texel of the finest scaled sprite
+            let step = tf.scale.truncate().min(other_tf.scale.truncate()).min_element();
+            if !masks_overlap(&masks, min, max, step) {
+                continue;
+            }
+        }
+
+        if forward {
+            collision_events.send(CollisionEvent {
+                entity,
+                layer: collider.layer,
+                other,
+                other_layer: other_collider.layer,
+            });
+        }
+        if backward {
+            collision_events.send(CollisionEvent {
+                entity: other,
+                layer: other_collider.layer,
+                other: entity,
+                other_layer: collider.layer,
+            });
+        }
+    }
+}
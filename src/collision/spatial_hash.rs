@@ -0,0 +1,72 @@
+// Note: only depends on bevy, so that benches/broadphase.rs can include it as-is.
+
+use bevy::{prelude::*, utils::StableHashMap};
+
+// region: --- Spatial Hash
+
+struct Entry {
+    entity: Entity,
+    min: Vec2, // AABB min corner, to dedup pairs spanning several cells
+}
+
+/// Uniform grid broadphase. Entities are bucketed in every cell their AABB overlaps,
+/// so only entities sharing a cell are ever tested against each other.
+#[derive(Resource)]
+pub struct SpatialHash {
+    cell_size: f32,
+    cells: StableHashMap<IVec2, Vec<usize>>, // cell -> entry indices, stable order so that replays are deterministic
+    entries: Vec<Entry>,
+}
+
+impl SpatialHash {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: StableHashMap::default(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Remove all entries, keeping the allocations for the next rebuild.
+    pub fn clear(&mut self) {
+        self.cells.values_mut().for_each(Vec::clear);
+        self.entries.clear();
+    }
+
+    pub fn insert(&mut self, entity: Entity, center: Vec2, size: Vec2) {
+        let (min, max) = (center - size / 2., center + size / 2.);
+        let index = self.entries.len();
+        self.entries.push(Entry { entity, min });
+
+        let (min_cell, max_cell) = (self.cell(min), self.cell(max));
+        for x in min_cell.x..=max_cell.x {
+            for y in min_cell.y..=max_cell.y {
+                self.cells.entry(IVec2::new(x, y)).or_default().push(index);
+            }
+        }
+    }
+
+    /// Every pair of entities sharing a cell, each pair reported once.
+    pub fn candidate_pairs(&self) -> Vec<(Entity, Entity)> {
+        let mut pairs = Vec::new();
+        for (cell, indices) in self.cells.iter() {
+            for (i, &a) in indices.iter().enumerate() {
+                for &b in &indices[i + 1..] {
+                    let (a, b) = (&self.entries[a], &self.entries[b]);
+                    // a pair spanning several shared cells is only reported by the cell
+                    // holding the min corner of their overlap
+                    if self.cell(a.min.max(b.min)) == *cell {
+                        pairs.push((a.entity, b.entity));
+                    }
+                }
+            }
+        }
+        pairs
+    }
+
+    fn cell(&self, point: Vec2) -> IVec2 {
+        (point / self.cell_size).floor().as_ivec2()
+    }
+}
+
+// endregion: --- Spatial Hash
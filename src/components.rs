@@ -1,7 +1,10 @@
-use bevy::{prelude::{Component, Vec2, Vec3}, time::{Timer, TimerMode}};
+use bevy::{prelude::{Component, Handle, Image, Vec2, Vec3}, time::{Timer, TimerMode}};
+use serde::Deserialize;
+
+use crate::{PLAYER_INVULNERABLE_DURATION, POWER_UP_DURATION, SHIELD_DURATION, collision::Collider};
 
 // region: --- Common Components
-#[derive(Component)]
+#[derive(Component, Clone, Copy)]
 pub struct Velocity {
     pub x: f32,
     pub y: f32,
@@ -15,9 +18,26 @@ pub struct Movable {
 #[derive(Component)]
 pub struct Laser;
 
-#[derive(Component)]
+#[derive(Component, Clone, Copy)]
 pub struct SpriteSize(pub Vec2);
 
+/// Hit points, the entity is destroyed when `current` reaches 0.
+#[derive(Component)]
+pub struct Health {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl Health {
+    pub fn new(max: u32) -> Self {
+        Self { current: max, max }
+    }
+}
+
+/// Damage dealt on hit (carried by lasers).
+#[derive(Component)]
+pub struct Damage(pub u32);
+
 impl From<(f32, f32)> for SpriteSize {
     fn from(val: (f32, f32)) -> Self {
         SpriteSize(Vec2::new(val.0, val.1))
@@ -32,6 +52,46 @@ pub struct Player;
 #[derive(Component)]
 pub struct FromPlayer;
 
+/// Short grace period after a respawn during which enemy lasers pass through.
+#[derive(Component)]
+pub struct Invulnerable(pub Timer);
+
+impl Default for Invulnerable {
+    fn default() -> Self {
+        Self(Timer::from_seconds(PLAYER_INVULNERABLE_DURATION, TimerMode::Once))
+    }
+}
+
+/// Absorbs the next hit (consulted by the damage pipeline), for a limited time.
+#[derive(Component)]
+pub struct Shield(pub Timer);
+
+impl Default for Shield {
+    fn default() -> Self {
+        Self(Timer::from_seconds(SHIELD_DURATION, TimerMode::Once))
+    }
+}
+
+/// Fire a fan of three lasers, for a limited time.
+#[derive(Component)]
+pub struct SpreadShot(pub Timer);
+
+impl Default for SpreadShot {
+    fn default() -> Self {
+        Self(Timer::from_seconds(POWER_UP_DURATION, TimerMode::Once))
+    }
+}
+
+/// Keep firing while the fire key is held, for a limited time.
+#[derive(Component)]
+pub struct RapidFire(pub Timer);
+
+impl Default for RapidFire {
+    fn default() -> Self {
+        Self(Timer::from_seconds(POWER_UP_DURATION, TimerMode::Once))
+    }
+}
+
 // endregion: --- Player Components
 
 // region: --- Enemy Components
@@ -39,13 +99,148 @@ pub struct FromPlayer;
 #[derive(Component)]
 pub struct Enemy;
 
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum EnemyKind {
+    Grunt,
+    Tank, // slow, takes several hits
+    Diver, // fast, rarely fires
+    Shooter, // fires the most
+}
+
+impl EnemyKind {
+    pub fn health(&self) -> u32 {
+        match self {
+            EnemyKind::Grunt => 1,
+            EnemyKind::Tank => 3,
+            EnemyKind::Diver => 1,
+            EnemyKind::Shooter => 2,
+        }
+    }
+
+    pub fn points(&self) -> u32 {
+        match self {
+            EnemyKind::Grunt => 100,
+            EnemyKind::Tank => 300,
+            EnemyKind::Diver => 200,
+            EnemyKind::Shooter => 250,
+        }
+    }
+
+    /// Applied on top of the wave speed.
+    pub fn speed_multiplier(&self) -> f32 {
+        match self {
+            EnemyKind::Grunt => 1.,
+            EnemyKind::Tank => 0.7,
+            EnemyKind::Diver => 1.6,
+            EnemyKind::Shooter => 0.9,
+        }
+    }
+
+    /// Applied on top of the enemy fire interval (larger fires less often).
+    pub fn fire_interval_multiplier(&self) -> f32 {
+        match self {
+            EnemyKind::Grunt => 1.25,
+            EnemyKind::Tank => 2.,
+            EnemyKind::Diver => 4.,
+            EnemyKind::Shooter => 1.,
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct FromEnemy;
 
+/// Time until the enemy's next shot, re-rolled after each one. Once finished,
+/// the enemy waits for a clear shot at the player.
+#[derive(Component)]
+pub struct FireTimer(pub Timer);
+
+/// Base points awarded for destroying an enemy, before the combo multiplier.
+#[derive(Component)]
+pub struct Points(pub u32);
 
 
 // endregion: --- Enemy Components
 
+// region: --- Weapon Components
+
+/// Template of the projectiles a `Weapon` fires.
+#[derive(Clone)]
+pub struct Projectile {
+    pub texture: Handle<Image>,
+    pub size: SpriteSize,
+    pub velocity: Velocity, // the sprite is turned to face it
+    pub damage: u32,
+    pub collider: Collider,
+}
+
+/// Where a projectile leaves the ship, relative to its center.
+#[derive(Clone, Copy)]
+pub struct Muzzle {
+    pub offset: Vec2,
+    pub slope: f32, // added to the projectile's horizontal velocity, to fan out
+}
+
+impl Muzzle {
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { offset: Vec2::new(x, y), slope: 0. }
+    }
+
+    pub const fn angled(x: f32, y: f32, slope: f32) -> Self {
+        Self { offset: Vec2::new(x, y), slope }
+    }
+}
+
+/// Fires one `projectile` per muzzle, at most once per `cooldown`.
+#[derive(Component, Clone)]
+pub struct Weapon {
+    pub projectile: Projectile,
+    pub muzzles: Vec<Muzzle>,
+    pub cooldown: f64, // seconds
+    pub auto_fire: bool, // keeps firing while the trigger is held
+    pub last_fire: Option<f64>, // elapsed seconds
+}
+
+// endregion: --- Weapon Components
+
+// region: --- Power-up Components
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowerUpKind {
+    SpreadShot,
+    RapidFire,
+    Shield,
+    ExtraLife,
+}
+
+impl PowerUpKind {
+    pub const ALL: [PowerUpKind; 4] = [PowerUpKind::SpreadShot, PowerUpKind::RapidFire, PowerUpKind::Shield, PowerUpKind::ExtraLife];
+
+    /// Relative chance to be the one dropped.
+    pub fn weight(&self) -> u32 {
+        match self {
+            PowerUpKind::SpreadShot => 3,
+            PowerUpKind::RapidFire => 3,
+            PowerUpKind::Shield => 2,
+            PowerUpKind::ExtraLife => 1,
+        }
+    }
+}
+
+/// Falling pickup, collected by the player ship.
+#[derive(Component)]
+pub struct PowerUp(pub PowerUpKind);
+
+// endregion: --- Power-up Components
+
+// region: --- Bunker Components
+
+/// One destructible cell of a bunker, eroded by the lasers of both sides.
+#[derive(Component)]
+pub struct BunkerCell;
+
+// endregion: --- Bunker Components
+
 // region: --- Explosion Components
 
 #[derive(Component)]
@@ -0,0 +1,196 @@
+use bevy::{prelude::*, input::gamepad::gamepad_connection_system};
+use serde::{Deserialize, Serialize};
+
+use crate::{GAMEPAD_DEADZONE, settings::Settings, state::GameState};
+
+// region: --- Actions
+
+/// What the player does, whatever the key or gamepad button doing it.
+/// Gameplay reads `Input<Action>` (and `MoveAxis`), never the devices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    Fire,
+    Pause,
+}
+
+impl Action {
+    pub const ALL: [Action; 4] = [Action::MoveLeft, Action::MoveRight, Action::Fire, Action::Pause];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::Fire => "Fire",
+            Action::Pause => "Pause",
+        }
+    }
+
+    /// Gamepad buttons, not rebindable.
+    fn buttons(&self) -> &'static [GamepadButtonType] {
+        match self {
+            Action::MoveLeft => &[GamepadButtonType::DPadLeft],
+            Action::MoveRight => &[GamepadButtonType::DPadRight],
+            Action::Fire => &[GamepadButtonType::South, GamepadButtonType::RightTrigger],
+            Action::Pause => &[GamepadButtonType::Start],
+        }
+    }
+}
+
+/// Horizontal movement, from -1 (full left) to 1 (full right). Digital keys and d-pad
+/// give -1 / 0 / 1, the left stick anything in between.
+#[derive(Resource, Default, Debug)]
+pub struct MoveAxis(pub f32);
+
+// endregion: --- Actions
+
+// region: --- Bindings
+
+/// Keys of each action, persisted with the settings. The first key is the one
+/// rebound from the options menu, the others are alternates.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bindings {
+    pub move_left: Vec<KeyCode>,
+    pub move_right: Vec<KeyCode>,
+    pub fire: Vec<KeyCode>,
+    pub pause: Vec<KeyCode>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self {
+            move_left: vec![KeyCode::Left, KeyCode::A],
+            move_right: vec![KeyCode::Right, KeyCode::D],
+            fire: vec![KeyCode::Space, KeyCode::W],
+            pause: vec![KeyCode::Escape, KeyCode::P],
+        }
+    }
+}
+
+impl Bindings {
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        match action {
+            Action::MoveLeft => &self.move_left,
+            Action::MoveRight => &self.move_right,
+            Action::Fire => &self.fire,
+            Action::Pause => &self.pause,
+        }
+    }
+
+    fn keys_mut(&mut self, action: Action) -> &mut Vec<KeyCode> {
+        match action {
+            Action::MoveLeft => &mut self.move_left,
+            Action::MoveRight => &mut self.move_right,
+            Action::Fire => &mut self.fire,
+            Action::Pause => &mut self.pause,
+        }
+    }
+
+    /// Make `key` the primary key of `action`. A key drives a single action,
+    /// so it is unbound from any other.
+    pub fn rebind(&mut self, action: Action, key: KeyCode) {
+        for other in Action::ALL {
+            self.keys_mut(other).retain(|bound| *bound != key);
+        }
+        let keys = self.keys_mut(action);
+        if keys.is_empty() {
+            keys.push(key);
+        } else {
+            keys[0] = key;
+        }
+    }
+
+    /// Keys of `action` for display, e.g. "Escape / P".
+    pub fn describe(&self, action: Action) -> String {
+        let keys: Vec<String> = self.keys(action).iter().map(|key| format!("{key:?}")).collect();
+        if keys.is_empty() {
+            "(unbound)".to_string()
+        } else {
+            keys.join(" / ")
+        }
+    }
+}
+
+// endregion: --- Bindings
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Input<Action>>()
+            .init_resource::<MoveAxis>()
+            // after the devices, including gamepads (un)plugged this frame
+            .add_system_to_stage(CoreStage::PreUpdate, action_input_system.after(gamepad_connection_system))
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(gamepad_disconnect_system)
+            );
+    }
+}
+
+/// Map the keyboard and gamepads to actions. Like `Input<KeyCode>`, an action is only
+/// just pressed on a fresh press, so `reset` (when a state change consumes it) sticks
+/// until it is released.
+fn action_input_system(
+    kb: Res<Input<KeyCode>>,
+    buttons: Res<Input<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    gamepads: Res<Gamepads>,
+    settings: Res<Settings>,
+    mut actions: ResMut<Input<Action>>,
+    mut move_axis: ResMut<MoveAxis>,
+) {
+    actions.clear();
+
+    for action in Action::ALL {
+        let keys = settings.bindings.keys(action);
+        let gamepad_buttons = || {
+            gamepads.iter().flat_map(|gamepad| action.buttons().iter().map(move |button| GamepadButton::new(gamepad, *button)))
+        };
+
+        if kb.any_just_pressed(keys.iter().copied()) || buttons.any_just_pressed(gamepad_buttons()) {
+            actions.press(action);
+        } else if !kb.any_pressed(keys.iter().copied()) && !buttons.any_pressed(gamepad_buttons()) {
+            actions.release(action);
+        }
+    }
+
+    // digital input wins over the stick
+    let digital = match (actions.pressed(Action::MoveLeft), actions.pressed(Action::MoveRight)) {
+        (true, false) => -1.,
+        (false, true) => 1.,
+        _ => 0.,
+    };
+    move_axis.0 = if digital != 0. {
+        digital
+    } else {
+        gamepads
+            .iter()
+            .filter_map(|gamepad| axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX)))
+            .map(apply_deadzone)
+            .find(|value| *value != 0.)
+            .unwrap_or(0.)
+    };
+}
+
+/// Zero within the deadzone, then rescaled so the stick still covers 0 to 1.
+fn apply_deadzone(value: f32) -> f32 {
+    if value.abs() < GAMEPAD_DEADZONE {
+        0.
+    } else {
+        value.signum() * ((value.abs() - GAMEPAD_DEADZONE) / (1. - GAMEPAD_DEADZONE)).min(1.)
+    }
+}
+
+/// Pause when a gamepad is unplugged mid-game (connections are logged by bevy).
+fn gamepad_disconnect_system(mut gamepad_events: EventReader<GamepadEvent>, mut state: ResMut<State<GameState>>) {
+    let disconnected = gamepad_events
+        .iter()
+        .any(|event| event.event_type == GamepadEventType::Disconnected);
+    if disconnected {
+        state.push(GameState::Paused).ok();
+    }
+}
@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+
+use crate::{components::{Health, Shield}, state::GameState};
+
+// region: --- Damage Events
+
+/// Sent by the hit systems, one per hit. Resolved against the target's `Health`.
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: u32,
+}
+
+/// Sent once, when a target's `Health` drops to 0. The target is not despawned here,
+/// the gameplay systems reacting to it own that (explosion, score, respawn...).
+pub struct DeathEvent {
+    pub entity: Entity,
+    pub translation: Vec3,
+}
+
+// endregion: --- Damage Events
+
+/// Damage pipeline ordering, within the Playing update:
+/// - hit systems send `DamageEvent`s `.before(DamageLabel::Apply)`
+/// - death handlers consume `DeathEvent`s `.after(DamageLabel::Apply)`
+#[derive(SystemLabel, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DamageLabel {
+    Apply,
+}
+
+pub struct DamagePlugin;
+
+impl Plugin for DamagePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<DamageEvent>()
+            .add_event::<DeathEvent>()
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(damage_apply_system.label(DamageLabel::Apply))
+            );
+    }
+}
+
+/// Single place where damage is resolved against `Health`.
+/// Damage modifiers (armor, shields, power-ups) are components on the target, consulted here.
+fn damage_apply_system(
+    mut commands: Commands,
+    mut damage_events: EventReader<DamageEvent>,
+    mut death_events: EventWriter<DeathEvent>,
+    mut query: Query<(&mut Health, &Transform, Option<&mut Sprite>, Option<&Shield>)>,
+) {
+    for event in damage_events.iter() {
+        let Ok((mut health, tf, sprite, shield)) = query.get_mut(event.target) else {
+            continue;
+        };
+        // already dead this frame
+        if health.current == 0 {
+            continue;
+        }
+        // the shield takes the hit (and whatever else hits this frame), then is gone
+        if shield.is_some() {
+            commands.entity(event.target).remove::<Shield>();
+            continue;
+        }
+
+        health.current = health.current.saturating_sub(event.amount);
+
+        if health.current == 0 {
+            death_events.send(DeathEvent {
+                entity: event.target,
+                translation: tf.translation,
+            });
+        } else if let Some(mut sprite) = sprite {
+            // tint toward red as the target wears down
+            let ratio = health.current as f32 / health.max as f32;
+            sprite.color = Color::rgb(1., ratio, ratio);
+        }
+    }
+}
@@ -1,7 +1,10 @@
-use bevy::{prelude::*, audio::CpalSample};
-use rand::{thread_rng, Rng};
+use bevy::prelude::*;
+use rand::{Rng, distributions::WeightedIndex, prelude::Distribution};
+use serde::Deserialize;
 
-use crate::{WinSize, FORMATION_MEMBERS_MAX, BASE_SPEED};
+use crate::{BASE_SPEED, components::EnemyKind, rng::GameRng, playfield::Playfield};
+
+use super::wave::WaveConfig;
 
 // Component Enemy Formation
 #[derive(Clone, Component)]
@@ -11,6 +14,27 @@ pub struct Formation {
     pub pivot: (f32, f32),
     pub speed: f32,
     pub angle: f32, // change per tick
+    pub kind: EnemyKind, // all members of a formation share the same kind
+}
+
+// Formation parameters, authored per wave (see assets/waves)
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct FormationConfig {
+    pub start_margin: f32, // how far off screen formations enter from
+    pub pivot_span: (f32, f32), // max pivot x/y, as a fraction of the playfield w/h
+    pub radius_x: (f32, f32), // min/max
+    pub radius_y: (f32, f32), // min/max
+}
+impl Default for FormationConfig {
+    fn default() -> Self {
+        Self {
+            start_margin: 100.,
+            pivot_span: (0.25, 0.4),
+            radius_x: (80., 150.),
+            radius_y: (100., 100.),
+        }
+    }
 }
 
 // Resource Formation Maker
@@ -21,8 +45,8 @@ pub struct FormationMaker {
     current_members: u32
 }
 impl FormationMaker {
-    pub fn make(&mut self, win_size: &WinSize) -> Formation {
-        match (&self.current_template, self.current_members > FORMATION_MEMBERS_MAX) {
+    pub fn make(&mut self, playfield: &Playfield, wave: &WaveConfig, rng: &mut GameRng) -> Formation {
+        match (&self.current_template, self.current_members >= wave.formation_members()) {
             // if has current template and within max members
             (Some(tmpl), false) => {
                 self.current_members += 1;
@@ -30,30 +54,35 @@ impl FormationMaker {
             }
             // if first formation or previous is full - create a new one
             (None, _) | (_, true) => {
-                let mut rng = thread_rng();
+                let config = &wave.formation;
                 // compute start x/y
-                let w_span = win_size.w / 2. + 100.;
-                let h_span = win_size.h / 2. + 100.;
+                let w_span = playfield.w / 2. + config.start_margin;
+                let h_span = playfield.h / 2. + config.start_margin;
                 let x = if rng.gen_bool(0.5) { w_span } else { -w_span };
-                let y = rng.gen_range(-h_span..h_span).to_f32();
+                let y = rng.gen_range(-h_span..h_span);
                 let start = (x, y);
                 // compute pivot x/y
-                let w_span = win_size.w / 4.;
-                let h_span = win_size.h / 3. + 50.;
-                let pivot = (rng.gen_range(-w_span..w_span), rng.gen_range(0.0..h_span));
+                let w_span = playfield.w * config.pivot_span.0;
+                let h_span = playfield.h * config.pivot_span.1;
+                let pivot = (rng.gen_range(-w_span..=w_span), rng.gen_range(0.0..=h_span));
                 // compute radius
-                let radius = (rng.gen_range(80.0..150.), 100.);
+                let (radius_x, radius_y) = (config.radius_x, config.radius_y);
+                let radius = (rng.gen_range(radius_x.0..=radius_x.1), rng.gen_range(radius_y.0..=radius_y.1));
                 // compute start angle
                 let angle = (y - pivot.1 ).atan2(x - pivot.0);
-                // speed - fixed for now
-                let speed = BASE_SPEED;
+                // pick the kind from the wave weights
+                let kind = WeightedIndex::new(wave.kinds.iter().map(|(_, weight)| *weight))
+                    .map_or(EnemyKind::Grunt, |index| wave.kinds[index.sample(rng)].0);
+                // speed - scaled by the wave difficulty and the kind
+                let speed = BASE_SPEED * wave.speed_multiplier * kind.speed_multiplier();
                 // create formation
                 let formation = Formation {
                     start,
                     radius,
                     pivot,
                     speed,
-                    angle
+                    angle,
+                    kind
                 };
                 // store as template
                 self.current_template = Some(formation.clone());
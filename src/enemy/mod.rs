@@ -1,11 +1,13 @@
 use std::{f32::consts::PI};
-use bevy::{prelude::*, time::FixedTimestep, ecs::schedule::ShouldRun};
-use rand::{thread_rng, Rng};
-use crate::{GameTextures, SPRITE_SCALE, WinSize, components::{Enemy, SpriteSize, Velocity, Movable, FromEnemy, Laser}, ENEMY_LASER_SIZE, ENEMY_SIZE, ENEMY_MAX_COUNT, EnemyCount, TIME_STEP};
+use bevy::prelude::*;
+use rand::Rng;
+use crate::{GameTextures, SPRITE_SCALE, components::{Enemy, SpriteSize, Points, Health, EnemyKind, Weapon, FireTimer, FromEnemy, Laser, Player}, ENEMY_SIZE, EnemyCount, GameTime, ENEMY_FIRE_INTERVAL, ENEMY_FIRE_JITTER, ENEMY_FIRE_CONE, ENEMY_MAX_LASERS, state::{GameState, despawn_with}, collision::{Collider, Layer, PixelPerfect}, rng::GameRng, playfield::Playfield, sound::SoundEvent};
 
 use self::formation::{FormationMaker, Formation};
+use self::wave::{Wave, WaveLabel, WavePlugin};
 
 mod formation;
+pub mod wave;
 
 
 
@@ -15,34 +17,44 @@ impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         app
             .insert_resource(FormationMaker::default())
+            .add_plugin(WavePlugin)
             .add_system_set(
-                SystemSet::new()
-                    .with_run_criteria(FixedTimestep::step(2.))
-                    .with_system(enemy_spawn_system)
+                SystemSet::on_enter(GameState::Playing)
+                    .with_system(enemy_reset_system)
             )
             .add_system_set(
-                SystemSet::new()
-                    .with_run_criteria(enemy_fire_criteria)
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(enemy_spawn_system.after(WaveLabel))
+                    .with_system(enemy_movement_system)
                     .with_system(enemy_fire_system)
             )
-            .add_system(enemy_movement_system);
+            .add_system_set(
+                SystemSet::on_exit(GameState::Playing)
+                    .with_system(despawn_with::<Enemy>)
+            );
     }
 }
 
+fn enemy_reset_system(
+    mut enemy_count: ResMut<EnemyCount>,
+    mut formation_maker: ResMut<FormationMaker>,
+) {
+    enemy_count.0 = 0;
+    *formation_maker = FormationMaker::default();
+}
+
 fn enemy_movement_system(
-    // time: Res<Time>,
+    time: Res<GameTime>,
     mut query: Query<(&mut Transform, &mut Formation), With<Enemy>>,    
 )
 {
-    // let now = time.elapsed_seconds();
+    let delta = time.delta_seconds();
     for (mut transform, mut formation) in query.iter_mut() {
         // current pos
         let (x_org, y_org) = (transform.translation.x, transform.translation.y);
 
-        let delta = TIME_STEP * formation.speed;
-
         // max distance
-        let max_distance = delta; 
+        let max_distance = delta * formation.speed;
 
         // fixtures
         let dir: f32 = if formation.start.0 < 0. { 1. } else { -1. }; // 1 is counter-clockwise, -1 is clockwise
@@ -50,7 +62,7 @@ fn enemy_movement_system(
         let (x_radius, y_radius) = formation.radius;
 
         // compute next angle (based on time)
-        let angle = formation.angle + dir * formation.speed * TIME_STEP / (x_radius.min(y_radius) * PI / 2.);
+        let angle = formation.angle + dir * formation.speed * delta / (x_radius.min(y_radius) * PI / 2.);
 
         // compute target x/y
         let x_dst = x_radius * angle.cos() + x_pivot;
@@ -73,7 +85,8 @@ fn enemy_movement_system(
         let y = if dy > 0. { y.max(y_dst) } else { y.min(y_dst) };
 
         // start rotating the formation angle only when sprite is on or close to ellipse
-        if distance < max_distance * formation.speed / 20. {
+        // (within speed / 1200 seconds of travel - independent of the frame duration)
+        if distance < formation.speed * formation.speed / 1200. {
             formation.angle = angle
         }
 
@@ -84,23 +97,33 @@ fn enemy_movement_system(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn enemy_spawn_system(
     mut commands: Commands, 
+    time: Res<GameTime>,
+    mut wave: ResMut<Wave>,
     mut enemy_count: ResMut<EnemyCount>,   
     mut formation_maker: ResMut<FormationMaker>, 
     game_textures: Res<GameTextures>,
-    win_size: Res<WinSize>
+    playfield: Res<Playfield>,
+    mut rng: ResMut<GameRng>,
 ) {
-    if enemy_count.0 >= ENEMY_MAX_COUNT {
+    // spawn at the wave cadence, until the wave budget is used up
+    if !wave.is_spawning() || !wave.spawn_timer.tick(time.delta()).just_finished() {
         return
     }
+    // first spawn of a wave - don't carry over the previous wave's formation
+    if wave.spawned == 0 {
+        *formation_maker = FormationMaker::default();
+    }
     // get formation and start x/y
-    let formation = formation_maker.make(&win_size);
+    let formation = formation_maker.make(&playfield, &wave.config, &mut rng);
     let (x, y) = formation.start;
+    let kind = formation.kind;
 
 
     commands.spawn(SpriteBundle {
-        texture: game_textures.enemy.clone(),
+        texture: game_textures.enemy(kind),
         transform: Transform {
             translation: Vec3::new(x, y, 10.),
             scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.),
@@ -110,48 +133,66 @@ fn enemy_spawn_system(
     })
     .insert(SpriteSize::from(ENEMY_SIZE))
     .insert(Enemy)
+    .insert(kind)
+    .insert(Health::new(kind.health()))
+    .insert(Collider::new(Layer::Enemy, &[]))
+    .insert(PixelPerfect)
+    .insert(Points(kind.points()))
+    .insert(Weapon::enemy_cannon(&game_textures))
+    .insert(FireTimer(Timer::from_seconds(fire_delay(kind, &mut rng), TimerMode::Once)))
     .insert(formation);
     
-    enemy_count.0 += 1
+    enemy_count.0 += 1;
+    wave.spawned += 1;
 }
 
-fn enemy_fire_criteria(
-) -> ShouldRun {
-    if thread_rng().gen_bool(1. / 90.) {
-        ShouldRun::Yes
-    } else {
-        ShouldRun::No
-    }
+/// Seconds until the next shot of an enemy of `kind`, jittered so enemies don't fire in step.
+fn fire_delay(kind: EnemyKind, rng: &mut GameRng) -> f32 {
+    let jitter = rng.gen_range(1. - ENEMY_FIRE_JITTER..1. + ENEMY_FIRE_JITTER);
+    ENEMY_FIRE_INTERVAL * kind.fire_interval_multiplier() * jitter
 }
 
+/// Each enemy fires on its own timer, when the player is within its firing cone
+/// and there is room under the cap of enemy lasers on screen.
 fn enemy_fire_system(
     mut commands: Commands,
-    game_textures: Res<GameTextures>,
-    enemy_query: Query<&Transform, With<Enemy>>
+    time: Res<GameTime>,
+    mut rng: ResMut<GameRng>,
+    mut sound_events: EventWriter<SoundEvent>,
+    player_query: Query<&Transform, With<Player>>,
+    laser_query: Query<(), (With<Laser>, With<FromEnemy>)>,
+    mut enemy_query: Query<(&Transform, &EnemyKind, &mut Weapon, &mut FireTimer), With<Enemy>>
 ) {
-    for &tf in enemy_query.iter() {
-        let (x, y) = (tf.translation.x, tf.translation.y);
-        commands
-            .spawn(SpriteBundle {
-                texture: game_textures.enemy_laser.clone(),
-                transform: Transform {
-                    translation: Vec3::new(x, y - 15., 0.),
-                    rotation: Quat::from_rotation_x(PI),
-                    scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.),
-                    ..default()
-                },
-                ..default()
-            })
-            .insert(Laser)
-            .insert(SpriteSize::from(ENEMY_LASER_SIZE))
-            .insert(FromEnemy)
-            .insert(Movable {
-                auto_despawn: true
-            })
-            .insert(Velocity {
-                x: 0.,
-                y: -1.
-            });
+    let delta = time.delta();
+    let now = time.elapsed_seconds_f64();
+    let player = player_query.get_single().ok().map(|tf| tf.translation.truncate());
+    let cone = ENEMY_FIRE_CONE.to_radians().tan();
+    let mut lasers = laser_query.iter().count();
+
+    let mut fired = false;
+    for (tf, kind, mut weapon, mut fire_timer) in enemy_query.iter_mut() {
+        // the whole volley must fit under the cap
+        let room = lasers + weapon.muzzles.len() <= ENEMY_MAX_LASERS;
+        if !fire_timer.0.tick(delta).finished() || !room || !weapon.ready(now, weapon.cooldown) {
+            continue;
+        }
+        // only with the player below, within the cone
+        let Some(player) = player else {
+            continue;
+        };
+        let to_player = player - tf.translation.truncate();
+        if to_player.y >= 0. || to_player.x.abs() > -to_player.y * cone {
+            continue;
+        }
+
+        weapon.fire(&mut commands, tf, None, now);
+        lasers += weapon.muzzles.len();
+        fire_timer.0 = Timer::from_seconds(fire_delay(*kind, &mut rng), TimerMode::Once);
+        fired = true;
     }
-    
-}
\ No newline at end of file
+
+    // one sound per frame, however many fired
+    if fired {
+        sound_events.send(SoundEvent::EnemyFire);
+    }
+}
@@ -0,0 +1,313 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, asset::{AssetLoader, LoadContext, LoadedAsset, LoadState}, reflect::TypeUuid, utils::BoxedFuture};
+use serde::Deserialize;
+
+use crate::{GameFonts, GameTime, EnemyCount, WAVE_BANNER_DURATION, components::EnemyKind, state::{GameState, despawn_with, spawn_screen}};
+
+use super::formation::FormationConfig;
+
+const WAVES_FOLDER: &str = "waves";
+
+// region: --- Wave Config
+
+/// Parameters of a single wave, authored in `assets/waves/*.wave.ron`.
+#[derive(Clone, Debug, Deserialize, TypeUuid)]
+#[uuid = "6d1c3f0e-5b0a-4d8e-9a57-2f6a1b9e4c21"]
+pub struct WaveConfig {
+    pub enemies: u32, // enemy budget for the whole wave
+    pub spawn_interval: f32, // seconds between two spawns
+    pub formations: u32, // number of formations the budget is split into
+    pub speed_multiplier: f32, // applied to BASE_SPEED
+    #[serde(default = "default_kinds")]
+    pub kinds: Vec<(EnemyKind, u32)>, // kind and relative spawn weight, picked per formation
+    #[serde(default)]
+    pub formation: FormationConfig,
+}
+
+fn default_kinds() -> Vec<(EnemyKind, u32)> {
+    vec![(EnemyKind::Grunt, 1)]
+}
+
+impl Default for WaveConfig {
+    fn default() -> Self {
+        Self {
+            enemies: 4,
+            spawn_interval: 2.,
+            formations: 1,
+            speed_multiplier: 1.,
+            kinds: default_kinds(),
+            formation: FormationConfig::default(),
+        }
+    }
+}
+
+impl WaveConfig {
+    /// Built-in difficulty curve, used when no wave files are loaded.
+    pub fn for_wave(number: u32) -> Self {
+        Self::default().scaled(number.saturating_sub(1))
+    }
+
+    /// Harder version of this wave, `steps` waves later -
+    /// more enemies, spawning faster and moving quicker.
+    pub fn scaled(&self, steps: u32) -> Self {
+        Self {
+            enemies: (self.enemies + steps * 2).min(40),
+            spawn_interval: (self.spawn_interval * 0.9f32.powi(steps as i32)).max(0.5),
+            formations: self.formations + steps / 2,
+            speed_multiplier: (self.speed_multiplier + steps as f32 * 0.1).min(2.),
+            kinds: self.kinds.clone(),
+            formation: self.formation.clone(),
+        }
+    }
+
+    /// Max members per formation, so that the budget fits the formation count.
+    pub fn formation_members(&self) -> u32 {
+        self.enemies.div_ceil(self.formations.max(1))
+    }
+
+    /// Parse and validate a wave file.
+    pub fn from_ron(bytes: &[u8]) -> Result<Self, String> {
+        let config = ron::de::from_bytes::<WaveConfig>(bytes).map_err(|err| err.to_string())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let formation = &self.formation;
+        if self.enemies == 0 || self.formations == 0 {
+            Err("enemies and formations must be > 0".to_string())
+        } else if self.spawn_interval <= 0. || self.speed_multiplier <= 0. {
+            Err("spawn_interval and speed_multiplier must be > 0".to_string())
+        } else if self.kinds.iter().all(|(_, weight)| *weight == 0) {
+            Err("kinds must have at least one non-zero weight".to_string())
+        } else if formation.radius_x.0 > formation.radius_x.1 || formation.radius_y.0 > formation.radius_y.1 {
+            Err("formation radius ranges must be (min, max)".to_string())
+        } else if formation.pivot_span.0 < 0. || formation.pivot_span.1 < 0. {
+            Err("formation pivot_span must be >= 0".to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Default)]
+struct WaveConfigLoader;
+
+impl AssetLoader for WaveConfigLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let config = WaveConfig::from_ron(bytes)
+                .map_err(|err| bevy::asset::Error::msg(format!("{:?}: {err}", load_context.path())))?;
+            load_context.set_default_asset(LoadedAsset::new(config));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["wave.ron"]
+    }
+}
+
+/// Wave files, in file name order (wave 1 first).
+#[derive(Resource, Default)]
+pub struct WaveDefinitions(pub Vec<Handle<WaveConfig>>);
+
+impl WaveDefinitions {
+    /// Config for wave `number`, from its own file. Past the end of the list, or when its file
+    /// is missing or failed, the closest earlier loaded wave is scaled up instead - and the
+    /// built-in curve is used when there is none.
+    pub fn config(&self, number: u32, assets: &Assets<WaveConfig>) -> WaveConfig {
+        let index = number.saturating_sub(1) as usize;
+        let closest = self.0.iter().enumerate().take(index + 1).rev()
+            .find_map(|(slot, handle)| assets.get(handle).map(|config| (slot, config)));
+        match closest {
+            Some((slot, config)) if slot == index => config.clone(),
+            Some((slot, config)) => config.scaled((index - slot) as u32),
+            None => WaveConfig::for_wave(number),
+        }
+    }
+
+    /// True once every wave file is either loaded or failed to load.
+    pub fn is_loaded(&self, assets: &Assets<WaveConfig>, asset_server: &AssetServer) -> bool {
+        self.0
+            .iter()
+            .all(|handle| assets.contains(handle) || asset_server.get_load_state(handle) == LoadState::Failed)
+    }
+}
+
+// endregion: --- Wave Config
+
+// region: --- Wave Resource
+
+/// Sent when a wave begins (as its banner shows), the first one of a game included.
+pub struct WaveStartedEvent {
+    pub number: u32,
+}
+
+#[derive(Clone, Debug)]
+pub enum WavePhase {
+    Banner(Timer), // inter-wave banner, spawning starts when finished
+    Spawning,
+}
+
+#[derive(Resource)]
+pub struct Wave {
+    pub number: u32,
+    pub config: WaveConfig,
+    pub phase: WavePhase,
+    pub spawned: u32, // enemies spawned so far in this wave
+    pub spawn_timer: Timer,
+}
+
+impl Wave {
+    pub fn new(number: u32, config: WaveConfig) -> Self {
+        Self {
+            number,
+            phase: WavePhase::Banner(Timer::from_seconds(WAVE_BANNER_DURATION, TimerMode::Once)),
+            spawned: 0,
+            spawn_timer: Timer::from_seconds(config.spawn_interval, TimerMode::Repeating),
+            config,
+        }
+    }
+
+    pub fn is_spawning(&self) -> bool {
+        matches!(self.phase, WavePhase::Spawning) && self.spawned < self.config.enemies
+    }
+}
+
+impl Default for Wave {
+    fn default() -> Self {
+        Self::new(1, WaveConfig::for_wave(1))
+    }
+}
+
+// endregion: --- Wave Resource
+
+#[derive(Component)]
+struct WaveBanner;
+
+/// Wave progress, labelled so that spawning sees the phase of this frame.
+#[derive(SystemLabel, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WaveLabel;
+
+pub struct WavePlugin;
+
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<WaveStartedEvent>()
+            .add_asset::<WaveConfig>()
+            .init_asset_loader::<WaveConfigLoader>()
+            .insert_resource(Wave::default())
+            .insert_resource(WaveDefinitions::default())
+            .add_startup_system(wave_load_system)
+            .add_system_set(
+                SystemSet::on_enter(GameState::Playing)
+                    .with_system(wave_reset_system)
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(wave_progress_system.label(WaveLabel))
+            )
+            .add_system(wave_hot_reload_system)
+            .add_system_set(
+                SystemSet::on_exit(GameState::Playing)
+                    .with_system(despawn_with::<WaveBanner>)
+            );
+    }
+}
+
+fn wave_load_system(asset_server: Res<AssetServer>, mut definitions: ResMut<WaveDefinitions>) {
+    let handles = match asset_server.load_folder(WAVES_FOLDER) {
+        Ok(handles) => handles,
+        Err(err) => return warn!("cannot load {WAVES_FOLDER} folder ({err}), using built-in waves"),
+    };
+
+    // only keep wave files, other assets dropped in the folder are ignored
+    let mut handles: Vec<(String, Handle<WaveConfig>)> = handles
+        .into_iter()
+        .filter_map(|handle| {
+            let path = asset_server.get_handle_path(&handle)?.path().to_string_lossy().into_owned();
+            path.ends_with(".wave.ron").then(|| (path, handle.typed()))
+        })
+        .collect();
+    handles.sort_by(|a, b| a.0.cmp(&b.0));
+
+    definitions.0 = handles.into_iter().map(|(_, handle)| handle).collect();
+}
+
+/// Pick up wave files as they (re)load - `AssetPlugin` watches for changes,
+/// so editing a wave file updates the running wave in place.
+fn wave_hot_reload_system(
+    mut events: EventReader<AssetEvent<WaveConfig>>,
+    mut wave: ResMut<Wave>,
+    definitions: Res<WaveDefinitions>,
+    assets: Res<Assets<WaveConfig>>,
+) {
+    let reloaded = events.iter().any(|event| matches!(event, AssetEvent::Created { .. } | AssetEvent::Modified { .. }));
+    if !reloaded {
+        return;
+    }
+
+    let config = definitions.config(wave.number, &assets);
+    wave.spawn_timer.set_duration(Duration::from_secs_f32(config.spawn_interval));
+    wave.config = config;
+}
+
+fn wave_reset_system(
+    mut commands: Commands,
+    mut wave: ResMut<Wave>,
+    definitions: Res<WaveDefinitions>,
+    assets: Res<Assets<WaveConfig>>,
+    fonts: Res<GameFonts>,
+    mut wave_events: EventWriter<WaveStartedEvent>,
+) {
+    *wave = Wave::new(1, definitions.config(1, &assets));
+    spawn_wave_banner(&mut commands, &fonts, wave.number);
+    wave_events.send(WaveStartedEvent { number: wave.number });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn wave_progress_system(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    mut wave: ResMut<Wave>,
+    enemy_count: Res<EnemyCount>,
+    definitions: Res<WaveDefinitions>,
+    assets: Res<Assets<WaveConfig>>,
+    fonts: Res<GameFonts>,
+    mut wave_events: EventWriter<WaveStartedEvent>,
+    banner_query: Query<Entity, With<WaveBanner>>,
+) {
+    match &mut wave.phase {
+        WavePhase::Banner(timer) => {
+            if timer.tick(time.delta()).finished() {
+                for entity in banner_query.iter() {
+                    commands.entity(entity).despawn_recursive();
+                }
+                wave.phase = WavePhase::Spawning;
+            }
+        }
+        WavePhase::Spawning => {
+            // cleared when the whole budget was spawned and destroyed
+            if wave.spawned >= wave.config.enemies && enemy_count.0 == 0 {
+                let number = wave.number + 1;
+                *wave = Wave::new(number, definitions.config(number, &assets));
+                spawn_wave_banner(&mut commands, &fonts, wave.number);
+                wave_events.send(WaveStartedEvent { number: wave.number });
+            }
+        }
+    }
+}
+
+fn spawn_wave_banner(commands: &mut Commands, fonts: &GameFonts, number: u32) {
+    let title = format!("WAVE {number}");
+    let lines = [(title.as_str(), 56.), ("Get ready", 24.)];
+    let banner = spawn_screen(commands, &fonts.main, &lines);
+    commands.entity(banner).insert(WaveBanner);
+}
@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, audio::AudioSink, input::{keyboard::KeyboardInput, gamepad::GamepadEventRaw, ButtonState, InputPlugin}, time::TimeUpdateStrategy, window::{ReceivedCharacter, WindowResized}};
+
+use crate::{GamePlugin, WinSize, enemy::wave::{WaveConfig, WaveDefinitions}, highscore::HighScores, rng::GameRng, settings::Settings};
+
+/// Simulated frame duration of a headless update.
+pub const TICK: Duration = Duration::from_nanos(16_666_667);
+
+/// The game without window nor renderer, on a virtual `win_size` screen - no GPU needed.
+/// Randomness follows `seed`, and time only moves forward with `step`.
+pub fn headless_app(win_size: WinSize, seed: u64) -> App {
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin::default())
+        .add_plugin(InputPlugin)
+        // asset types, resources and events the game uses, normally registered by the render,
+        // window and audio plugins - sounds are queued but never played
+        .add_asset::<Image>()
+        .add_asset::<TextureAtlas>()
+        .add_asset::<AudioSource>()
+        .add_asset::<AudioSink>()
+        .init_resource::<Audio>()
+        .add_event::<ReceivedCharacter>()
+        .add_event::<WindowResized>()
+        .insert_resource(win_size)
+        .insert_resource(Settings::default()) // not the user's settings file
+        .insert_resource(HighScores::default()) // nor their saved high scores
+        .add_plugin(GamePlugin)
+        .insert_resource(GameRng::new(seed));
+    app
+}
+
+/// Run `ticks` updates of the app, each one `TICK` long.
+pub fn step(app: &mut App, ticks: u32) {
+    step_by(app, ticks, TICK);
+}
+
+/// Run `ticks` updates of the app, each one `tick` long - to play at another frame rate.
+pub fn step_by(app: &mut App, ticks: u32, tick: Duration) {
+    for _ in 0..ticks {
+        let time = app.world.resource::<Time>();
+        let last_update = time.last_update().unwrap_or_else(|| time.startup());
+        app.insert_resource(TimeUpdateStrategy::ManualInstant(last_update + tick));
+        app.update();
+    }
+}
+
+/// Step until the wave files are loaded, so that the first wave is the authored one
+/// (as in a played back game, which waits for them).
+pub fn wait_for_waves(app: &mut App) {
+    for _ in 0..600 {
+        step(app, 1);
+        let loaded = app.world.resource::<WaveDefinitions>()
+            .is_loaded(app.world.resource::<Assets<WaveConfig>>(), app.world.resource::<AssetServer>());
+        if loaded {
+            return;
+        }
+    }
+    panic!("wave files not loaded");
+}
+
+// region: --- Scripted Input
+
+/// Send a key press, picked up by `Input<KeyCode>` on the next update like a real key.
+pub fn press_key(app: &mut App, key: KeyCode) {
+    send_key(app, key, ButtonState::Pressed);
+}
+
+pub fn release_key(app: &mut App, key: KeyCode) {
+    send_key(app, key, ButtonState::Released);
+}
+
+fn send_key(app: &mut App, key: KeyCode, state: ButtonState) {
+    app.world.resource_mut::<Events<KeyboardInput>>().send(KeyboardInput {
+        scan_code: 0,
+        key_code: Some(key),
+        state,
+    });
+}
+
+/// Send a gamepad event (connection, button or axis change) as the gamepad backend would,
+/// so the gamepad resources are updated on the next update like with real hardware.
+pub fn send_gamepad_event(app: &mut App, gamepad: Gamepad, event_type: GamepadEventType) {
+    app.world.resource_mut::<Events<GamepadEventRaw>>().send(GamepadEventRaw::new(gamepad, event_type));
+}
+
+enum ScriptStep {
+    Hold(KeyCode, u32),
+    Wait(u32),
+}
+
+/// Keyboard input played tick by tick into a headless app, e.g.
+/// `InputScript::new().hold(KeyCode::Right, 30).press(KeyCode::Space).run(&mut app)`.
+#[derive(Default)]
+pub struct InputScript(Vec<ScriptStep>);
+
+impl InputScript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep `key` pressed for `ticks` ticks - it is released on the tick after.
+    pub fn hold(mut self, key: KeyCode, ticks: u32) -> Self {
+        self.0.push(ScriptStep::Hold(key, ticks));
+        self
+    }
+
+    /// Tap `key`, pressed for a single tick.
+    pub fn press(self, key: KeyCode) -> Self {
+        self.hold(key, 1)
+    }
+
+    pub fn wait(mut self, ticks: u32) -> Self {
+        self.0.push(ScriptStep::Wait(ticks));
+        self
+    }
+
+    pub fn run(&self, app: &mut App) {
+        for script_step in &self.0 {
+            match *script_step {
+                ScriptStep::Hold(key, ticks) => {
+                    press_key(app, key);
+                    step(app, ticks);
+                    release_key(app, key);
+                }
+                ScriptStep::Wait(ticks) => step(app, ticks),
+            }
+        }
+    }
+}
+
+// endregion: --- Scripted Input
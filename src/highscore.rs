@@ -0,0 +1,215 @@
+use std::{cmp::Reverse, fs, path::{Path, PathBuf}, time::{SystemTime, UNIX_EPOCH}};
+
+use bevy::{prelude::*, window::ReceivedCharacter};
+use serde::{Deserialize, Serialize};
+
+use crate::{GameFonts, score::Score, state::{GameState, despawn_with, spawn_screen}, HIGH_SCORES_MAX, INITIALS_LEN};
+
+const HIGH_SCORES_FILE: &str = "highscores.ron";
+
+// region: --- High Score Table
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HighScoreEntry {
+    pub initials: String,
+    pub score: u32,
+    pub date: String, // YYYY-MM-DD
+}
+
+/// Top scores, highest first, persisted as RON in the user data directory.
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+pub struct HighScores(pub Vec<HighScoreEntry>);
+
+impl Default for HighScores {
+    fn default() -> Self {
+        let initials = ["RST", "BVY", "ECS", "CPU", "GPU", "FPS", "UFO", "ZAP", "PEW", "ACE"];
+        Self(
+            initials
+                .iter()
+                .enumerate()
+                .map(|(i, initials)| HighScoreEntry {
+                    initials: initials.to_string(),
+                    score: 5000 - i as u32 * 500,
+                    date: "1978-06-01".to_string(),
+                })
+                .collect(),
+        )
+    }
+}
+
+impl HighScores {
+    /// Load the table from disk, falling back to the defaults if missing or corrupt.
+    pub fn load() -> Self {
+        high_scores_path().map_or_else(Self::default, |path| Self::load_from(&path))
+    }
+
+    /// Load the table from `path`, falling back to the defaults if missing or corrupt.
+    pub fn load_from(path: &Path) -> Self {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(_) => return Self::default(),
+        };
+        match ron::from_str::<HighScores>(&content) {
+            Ok(mut high_scores) => {
+                high_scores.normalize();
+                high_scores
+            }
+            Err(err) => {
+                warn!("invalid high score file {path:?} ({err}), using defaults");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        let Some(path) = high_scores_path() else {
+            return;
+        };
+        let content = match ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()) {
+            Ok(content) => content,
+            Err(err) => return warn!("cannot serialize high scores ({err})"),
+        };
+        if let Err(err) = path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|_| fs::write(&path, content)) {
+            warn!("cannot save high scores to {path:?} ({err})");
+        }
+    }
+
+    /// True if `score` would make it into the table.
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0 && (self.0.len() < HIGH_SCORES_MAX || self.0.iter().any(|entry| score > entry.score))
+    }
+
+    /// Insert a new entry at its rank, dropping the lowest one if the table is full.
+    pub fn insert(&mut self, entry: HighScoreEntry) {
+        self.0.push(entry);
+        self.normalize();
+    }
+
+    fn normalize(&mut self) {
+        // stable sort, so older entries win ties
+        self.0.sort_by_key(|entry| Reverse(entry.score));
+        self.0.truncate(HIGH_SCORES_MAX);
+    }
+}
+
+fn high_scores_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(env!("CARGO_PKG_NAME")).join(HIGH_SCORES_FILE))
+}
+
+/// Today's UTC date as YYYY-MM-DD.
+fn today() -> String {
+    utc_date(SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()))
+}
+
+/// UTC date of a Unix timestamp as YYYY-MM-DD (civil-from-days, no calendar crate needed).
+pub fn utc_date(secs: u64) -> String {
+    let z = (secs / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+// endregion: --- High Score Table
+
+// region: --- Name Entry Components
+
+#[derive(Component)]
+struct NameEntryScreen;
+
+#[derive(Component)]
+struct InitialsText;
+
+#[derive(Resource, Default)]
+struct Initials(String);
+
+// endregion: --- Name Entry Components
+
+pub struct HighScorePlugin;
+
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // normally inserted before the app is built (see `HighScores::load`)
+            .init_resource::<HighScores>()
+            .insert_resource(Initials::default())
+            .add_system_set(
+                SystemSet::on_enter(GameState::NameEntry)
+                    .with_system(name_entry_screen_system)
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::NameEntry)
+                    .with_system(name_entry_input_system)
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::NameEntry)
+                    .with_system(despawn_with::<NameEntryScreen>)
+            );
+    }
+}
+
+fn name_entry_screen_system(
+    mut commands: Commands,
+    mut initials: ResMut<Initials>,
+    fonts: Res<GameFonts>,
+    score: Res<Score>,
+) {
+    initials.0.clear();
+
+    let score = format!("Score {}", score.value);
+    let lines = [("NEW HIGH SCORE", 48.), (score.as_str(), 32.), ("Enter your initials", 24.)];
+    let screen = spawn_screen(&mut commands, &fonts.main, &lines);
+    commands.entity(screen).insert(NameEntryScreen).with_children(|parent| {
+        parent
+            .spawn(TextBundle::from_section(
+                "_".repeat(INITIALS_LEN),
+                TextStyle {
+                    font: fonts.main.clone(),
+                    font_size: 48.,
+                    color: Color::YELLOW,
+                },
+            ))
+            .insert(InitialsText);
+    });
+}
+
+fn name_entry_input_system(
+    mut kb: ResMut<Input<KeyCode>>,
+    mut char_events: EventReader<ReceivedCharacter>,
+    mut state: ResMut<State<GameState>>,
+    mut initials: ResMut<Initials>,
+    mut high_scores: ResMut<HighScores>,
+    score: Res<Score>,
+    mut text_query: Query<&mut Text, With<InitialsText>>,
+) {
+    for event in char_events.iter() {
+        if event.char.is_ascii_alphanumeric() && initials.0.len() < INITIALS_LEN {
+            initials.0.push(event.char.to_ascii_uppercase());
+        }
+    }
+    if kb.just_pressed(KeyCode::Back) {
+        initials.0.pop();
+    }
+
+    if kb.just_pressed(KeyCode::Return) && !initials.0.is_empty() {
+        kb.reset(KeyCode::Return);
+        high_scores.insert(HighScoreEntry {
+            initials: initials.0.clone(),
+            score: score.value,
+            date: today(),
+        });
+        high_scores.save();
+        state.set(GameState::GameOver).ok();
+    }
+
+    if initials.is_changed() {
+        if let Ok(mut text) = text_query.get_single_mut() {
+            text.sections[0].value = format!("{:_<width$}", initials.0, width = INITIALS_LEN);
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use bevy::prelude::*;
+
+use crate::{GameFonts, WinSize, EnemyCount, PlayerState, components::{Player, RapidFire, Shield, SpreadShot}, enemy::wave::Wave, powerup::PowerUpTimer, score::Score, state::{GameState, despawn_with}};
+
+// HUD metrics, as fractions of the window size
+const HUD_PADDING: f32 = 0.02; // of WinSize.w
+const HUD_FONT_SIZE: f32 = 0.03; // of WinSize.h
+
+// region: --- HUD Components
+
+#[derive(Component)]
+struct Hud;
+
+#[derive(Component)]
+enum HudText {
+    Score,
+    Wave,
+    Lives,
+    Enemies,
+    PowerUps,
+}
+
+// endregion: --- HUD Components
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system_set(
+                SystemSet::on_enter(GameState::Playing)
+                    .with_system(hud_spawn_system)
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(hud_layout_system)
+                    .with_system(hud_text_system)
+                    .with_system(hud_power_up_system)
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::Playing)
+                    .with_system(despawn_with::<Hud>)
+            );
+    }
+}
+
+fn hud_spawn_system(mut commands: Commands, fonts: Res<GameFonts>, win_size: Res<WinSize>) {
+    let style = TextStyle {
+        font: fonts.main.clone(),
+        font_size: win_size.h * HUD_FONT_SIZE,
+        color: Color::WHITE,
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: hud_style(&win_size),
+            ..default()
+        })
+        .insert(Hud)
+        .with_children(|parent| {
+            for hud_text in [HudText::Score, HudText::Wave, HudText::Lives, HudText::Enemies, HudText::PowerUps] {
+                parent.spawn(TextBundle::from_section("", style.clone())).insert(hud_text);
+            }
+        });
+}
+
+fn hud_style(win_size: &WinSize) -> Style {
+    Style {
+        position_type: PositionType::Absolute,
+        position: UiRect {
+            top: Val::Px(0.),
+            left: Val::Px(0.),
+            ..default()
+        },
+        size: Size::new(Val::Px(win_size.w), Val::Auto),
+        padding: UiRect::all(Val::Px(win_size.w * HUD_PADDING)),
+        justify_content: JustifyContent::SpaceBetween,
+        ..default()
+    }
+}
+
+/// Keep the HUD laid out relative to the current WinSize.
+fn hud_layout_system(
+    win_size: Res<WinSize>,
+    mut hud_query: Query<&mut Style, With<Hud>>,
+    mut text_query: Query<&mut Text, With<HudText>>,
+) {
+    if !win_size.is_changed() {
+        return;
+    }
+    for mut style in hud_query.iter_mut() {
+        *style = hud_style(&win_size);
+    }
+    for mut text in text_query.iter_mut() {
+        text.sections[0].style.font_size = win_size.h * HUD_FONT_SIZE;
+    }
+}
+
+/// Refresh the HUD texts, only when one of the displayed values changed (or the HUD was just spawned).
+fn hud_text_system(
+    score: Res<Score>,
+    wave: Res<Wave>,
+    player_state: Res<PlayerState>,
+    enemy_count: Res<EnemyCount>,
+    added_query: Query<(), Added<HudText>>,
+    mut query: Query<(&mut Text, &HudText)>,
+) {
+    let changed = score.is_changed() || wave.is_changed() || player_state.is_changed() || enemy_count.is_changed();
+    if !changed && added_query.is_empty() {
+        return;
+    }
+
+    for (mut text, hud_text) in query.iter_mut() {
+        text.sections[0].value = match hud_text {
+            HudText::PowerUps => continue, // see hud_power_up_system
+            HudText::Score if score.multiplier() > 1 => {
+                format!("SCORE {:06} x{}", score.value, score.multiplier())
+            }
+            HudText::Score => format!("SCORE {:06}", score.value),
+            HudText::Wave => format!("WAVE {}", wave.number),
+            HudText::Lives => format!("LIVES {}", player_state.lives),
+            HudText::Enemies => format!("ENEMIES {}", enemy_count.0),
+        };
+    }
+}
+
+/// Active power-ups with their seconds left, ticking every frame - only written when the text differs.
+#[allow(clippy::type_complexity)]
+fn hud_power_up_system(
+    player_query: Query<(Option<&SpreadShot>, Option<&RapidFire>, Option<&Shield>), With<Player>>,
+    mut query: Query<(&mut Text, &HudText)>,
+) {
+    let mut power_ups = Vec::new();
+    if let Ok((spread_shot, rapid_fire, shield)) = player_query.get_single() {
+        let seconds_left = [
+            ("SPREAD", spread_shot.map(|p| p.seconds_left())),
+            ("RAPID", rapid_fire.map(|p| p.seconds_left())),
+            ("SHIELD", shield.map(|p| p.seconds_left())),
+        ];
+        for (label, seconds) in seconds_left {
+            if let Some(seconds) = seconds {
+                power_ups.push(format!("{label} {seconds}"));
+            }
+        }
+    }
+    let value = power_ups.join(" ");
+
+    for (mut text, hud_text) in query.iter_mut() {
+        if matches!(hud_text, HudText::PowerUps) && text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
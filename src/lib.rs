@@ -0,0 +1,503 @@
+use std::{collections::{HashMap, HashSet}, time::Duration};
+
+#[allow(unused)]
+use bevy::{prelude::*, time::TimeSystem};
+use components::{Velocity, Movable, Laser, Enemy, ExplosionToSpawn, Explosion, ExplosionTimer, Player, Invulnerable, Points, EnemyKind, Health, Damage};
+use damage::{DamageEvent, DeathEvent, DamageLabel, DamagePlugin};
+use collision::{CollisionEvent, CollisionLabel, CollisionPlugin, Layer};
+use player::PlayerPlugin;
+use enemy::EnemyPlugin;
+use state::{GameState, StatePlugin, despawn_with};
+use score::{Score, ScorePlugin};
+use highscore::HighScorePlugin;
+use hud::HudPlugin;
+use rng::{GameRng, RngPlugin};
+use playfield::{Playfield, PlayfieldPlugin};
+use settings::SettingsPlugin;
+use controls::ControlsPlugin;
+use sound::{SoundEvent, SoundPlugin};
+use bunker::BunkerPlugin;
+use powerup::{PowerUpPlugin, power_up_bundle, roll_power_up};
+// use iyes_loopless::prelude::*;
+
+pub mod components;
+mod player;
+pub mod enemy;
+pub mod bunker;
+mod weapon;
+pub mod powerup;
+pub mod state;
+pub mod score;
+pub mod highscore;
+mod hud;
+pub mod damage;
+pub mod collision;
+mod rng;
+mod playfield;
+pub mod settings;
+pub mod controls;
+pub mod sound;
+pub mod replay;
+pub mod headless;
+
+// region: --- Asset Constants
+
+const PLAYER_SPRITE: &str = "player_a_01.png"; 
+const ENEMY_GRUNT_SPRITE: &str = "enemy_a_01.png"; 
+const ENEMY_TANK_SPRITE: &str = "enemy_b_01.png"; 
+const ENEMY_DIVER_SPRITE: &str = "enemy_c_01.png"; 
+const ENEMY_SHOOTER_SPRITE: &str = "enemy_d_01.png"; 
+const EXPLOSION_SHEET: &str = "explo_a_sheet.png"; 
+const EXPLOSION_LEN: usize = 16; 
+
+const PLAYER_SIZE: (f32, f32) = (144., 75.);
+const ENEMY_SIZE: (f32, f32) = (144., 75.);
+
+const PLAYER_LASER_SPRITE: &str = "laser_a_01.png"; 
+const PLAYER_LASER_SIZE: (f32, f32) = (9., 54.);
+
+const ENEMY_LASER_SPRITE: &str = "laser_b_01.png"; 
+const ENEMY_LASER_SIZE: (f32, f32) = (7., 55.);
+
+const SPRITE_SCALE: f32 = 0.5;
+
+const FONT: &str = "fonts/FiraSans-Bold.ttf";
+
+const PLAYER_LASER_SOUND: &str = "sounds/laser_player.wav";
+const ENEMY_LASER_SOUND: &str = "sounds/laser_enemy.wav";
+const EXPLOSION_SOUND: &str = "sounds/explosion.wav";
+const PLAYER_HIT_SOUND: &str = "sounds/player_hit.wav";
+const MUSIC: &str = "sounds/music.wav";
+
+// endregion: --- Asset Constants
+
+// region: --- Game Constants
+
+const PLAYFIELD_SIZE: (f32, f32) = (598., 676.); // logical, scaled to fit the window
+
+const MAX_TIME_STEP: f32 = 1.0 / 20.; // longer frames slow the game down rather than skip through collisions
+const BASE_SPEED: f32 = 300.;
+const GAMEPAD_DEADZONE: f32 = 0.2; // left stick, as a fraction of full tilt
+
+const PLAYER_RESPAWN_DELAY: f64 = 2.;
+const PLAYER_LIVES: u32 = 3;
+const PLAYER_HEALTH: u32 = 1;
+const PLAYER_LASER_DAMAGE: u32 = 1;
+const PLAYER_FIRE_COOLDOWN: f64 = 0.25; // seconds between two volleys, held or tapped
+const ENEMY_LASER_DAMAGE: u32 = 1;
+const ENEMY_FIRE_COOLDOWN: f64 = 1.; // minimum seconds between two shots of an enemy
+const ENEMY_FIRE_INTERVAL: f32 = 1.5; // average seconds between two shots of an enemy, before its kind
+const ENEMY_FIRE_JITTER: f32 = 0.5; // each interval is randomized by up to this fraction either way
+const ENEMY_FIRE_CONE: f32 = 25.; // degrees either side of straight down, the player must be within
+const ENEMY_MAX_LASERS: usize = 6; // on screen at once
+const PLAYER_INVULNERABLE_DURATION: f32 = 2.;
+const PLAYER_MAX_LIVES: u32 = 5;
+const WAVE_BANNER_DURATION: f32 = 2.;
+
+const POWER_UP_DROP_CHANCE: f64 = 0.12; // per enemy destroyed
+const POWER_UP_DURATION: f32 = 10.; // spread shot and rapid fire
+const SHIELD_DURATION: f32 = 8.;
+const RAPID_FIRE_INTERVAL: f64 = 0.12; // player weapon cooldown while rapid fire is on
+const SPREAD_SHOT_SLOPE: f32 = 0.25; // horizontal speed of the side lasers, vertical is 1
+
+const BUNKER_COUNT: usize = 4;
+const BUNKER_CELL_SIZE: f32 = 6.;
+const BUNKER_CELL_HEALTH: u32 = 2;
+const BUNKER_HEIGHT: f32 = 90.; // above the player spawn line
+
+const COLLISION_CELL_SIZE: f32 = 64.;
+
+const COMBO_WINDOW: f64 = 1.5;
+const COMBO_MAX_MULTIPLIER: u32 = 8;
+
+const HIGH_SCORES_MAX: usize = 10;
+const INITIALS_LEN: usize = 3;
+
+// endregion: --- Game Constants
+
+// region: --- Resources 
+#[derive(Resource)]
+pub struct WinSize {
+    pub w: f32,
+    pub h: f32
+}
+
+#[derive(Resource)]
+struct GameTextures {
+    player: Handle<Image>,
+    player_laser: Handle<Image>,
+    enemy_grunt: Handle<Image>,
+    enemy_tank: Handle<Image>,
+    enemy_diver: Handle<Image>,
+    enemy_shooter: Handle<Image>,
+    enemy_laser: Handle<Image>,
+    explosion: Handle<TextureAtlas>,
+}
+impl GameTextures {
+    pub fn enemy(&self, kind: EnemyKind) -> Handle<Image> {
+        match kind {
+            EnemyKind::Grunt => self.enemy_grunt.clone(),
+            EnemyKind::Tank => self.enemy_tank.clone(),
+            EnemyKind::Diver => self.enemy_diver.clone(),
+            EnemyKind::Shooter => self.enemy_shooter.clone(),
+        }
+    }
+}
+
+#[derive(Resource)]
+struct GameSounds {
+    player_laser: Handle<AudioSource>,
+    enemy_laser: Handle<AudioSource>,
+    explosion: Handle<AudioSource>,
+    player_hit: Handle<AudioSource>,
+    music: Handle<AudioSource>,
+}
+impl GameSounds {
+    pub fn effect(&self, sound: SoundEvent) -> Handle<AudioSource> {
+        match sound {
+            SoundEvent::PlayerFire => self.player_laser.clone(),
+            SoundEvent::EnemyFire => self.enemy_laser.clone(),
+            SoundEvent::Explosion => self.explosion.clone(),
+            SoundEvent::PlayerDeath => self.player_hit.clone(),
+        }
+    }
+}
+
+#[derive(Resource)]
+struct GameFonts {
+    main: Handle<Font>,
+}
+
+#[derive(Resource)]
+pub struct EnemyCount(pub u32);
+
+#[derive(Resource)]
+pub struct PlayerState {
+    pub on: bool, // alive
+    pub last_shot: f64, // -1 if not shot
+    pub lives: u32, // remaining ships, including the one on screen
+}
+impl Default for PlayerState {
+    fn default() -> Self {
+        Self {
+            on: false,
+            last_shot: -1.,
+            lives: PLAYER_LIVES,
+        }
+    }
+}
+impl PlayerState {
+    pub fn shot(&mut self, time: f64) {
+        self.on = false;
+        self.last_shot = time;
+        self.lives = self.lives.saturating_sub(1);
+    }
+
+    pub fn spawned(&mut self) {
+        self.on = true;
+        self.last_shot = -1.;
+    }
+}
+
+/// Gameplay clock: the frame delta clamped to `MAX_TIME_STEP`, and the time elapsed by those steps.
+/// Movement and every gameplay timer go by it, so they stay in step after a hitch
+/// (the game slows down rather than skipping ahead). Stopped outside of Playing, pause included.
+#[derive(Resource, Default)]
+pub struct GameTime {
+    delta: Duration,
+    elapsed: Duration,
+}
+impl GameTime {
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    pub fn elapsed_seconds_f64(&self) -> f64 {
+        self.elapsed.as_secs_f64()
+    }
+}
+// endregion: --- Resources
+
+/// Kinematics (`movable_system`) - input systems setting velocities run `.before` it.
+#[derive(SystemLabel, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MovementLabel;
+
+/// The whole game, on top of the bevy plugins provided by the app
+/// (`DefaultPlugins` for the game, `MinimalPlugins` when headless).
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<GameTime>()
+            .add_startup_system(setup_system)
+            .add_system_to_stage(CoreStage::First, game_time_system.after(TimeSystem))
+            .add_plugin(PlayfieldPlugin)
+            .add_plugin(StatePlugin)
+            .add_plugin(SettingsPlugin)
+            .add_plugin(ControlsPlugin)
+            .add_plugin(SoundPlugin)
+            .add_plugin(RngPlugin)
+            .add_plugin(ScorePlugin)
+            .add_plugin(HighScorePlugin)
+            .add_plugin(HudPlugin)
+            .add_plugin(DamagePlugin)
+            .add_plugin(CollisionPlugin)
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(movable_system.label(MovementLabel))
+                    .with_system(laser_hit_system.after(CollisionLabel).before(DamageLabel::Apply))
+                    .with_system(player_death_system.after(DamageLabel::Apply))
+                    .with_system(enemy_death_system.after(DamageLabel::Apply))
+                    .with_system(explosion_to_spawn_system)
+                    .with_system(explosion_animation_system)
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::Playing)
+                    .with_system(despawn_with::<Laser>)
+                    .with_system(despawn_with::<Explosion>)
+                    .with_system(despawn_with::<ExplosionToSpawn>)
+            )
+            .add_plugin(PlayerPlugin)
+            .add_plugin(EnemyPlugin)
+            .add_plugin(BunkerPlugin)
+            .add_plugin(PowerUpPlugin);
+    }
+}
+
+fn setup_system(
+    mut commands: Commands, 
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    windows: Option<Res<Windows>>
+) {
+    // capture window size (headless apps provide a virtual WinSize instead)
+    if let Some(window) = windows.as_ref().and_then(|windows| windows.get_primary()) {
+        let (win_w, win_h) = (window.width(), window.height());
+
+        // position window (for tutorial)
+        // window.set_position(MonitorSelection::Primary, IVec2::new(1964 /2, 3024 / 2));
+
+        // add WinSize resource
+        let win_size = WinSize { w: win_w, h: win_h};
+        commands.insert_resource(win_size);
+    }
+
+    // create explosion texture atlas
+    let texture_handle = asset_server.load(EXPLOSION_SHEET);
+    let texture_atlas = TextureAtlas::from_grid(texture_handle, Vec2::new(64., 64.), 4, 4, None, None);
+    let explosion = texture_atlases.add(texture_atlas);
+
+    // add GameTexture resource
+    let game_textures = GameTextures {
+        player: asset_server.load(PLAYER_SPRITE),
+        player_laser: asset_server.load(PLAYER_LASER_SPRITE),
+        enemy_grunt: asset_server.load(ENEMY_GRUNT_SPRITE),
+        enemy_tank: asset_server.load(ENEMY_TANK_SPRITE),
+        enemy_diver: asset_server.load(ENEMY_DIVER_SPRITE),
+        enemy_shooter: asset_server.load(ENEMY_SHOOTER_SPRITE),
+        enemy_laser: asset_server.load(ENEMY_LASER_SPRITE),
+        explosion
+    };
+
+    commands
+        .insert_resource(game_textures);
+
+    // add GameSounds resource
+    commands.insert_resource(GameSounds {
+        player_laser: asset_server.load(PLAYER_LASER_SOUND),
+        enemy_laser: asset_server.load(ENEMY_LASER_SOUND),
+        explosion: asset_server.load(EXPLOSION_SOUND),
+        player_hit: asset_server.load(PLAYER_HIT_SOUND),
+        music: asset_server.load(MUSIC),
+    });
+
+    // add GameFonts resource
+    commands.insert_resource(GameFonts {
+        main: asset_server.load(FONT),
+    });
+
+    commands
+        .insert_resource(EnemyCount(0));
+
+}
+
+fn game_time_system(time: Res<Time>, state: Res<State<GameState>>, mut game_time: ResMut<GameTime>) {
+    let delta = if state.current() == &GameState::Playing {
+        time.delta().min(Duration::from_secs_f32(MAX_TIME_STEP))
+    } else {
+        Duration::ZERO
+    };
+    game_time.delta = delta;
+    game_time.elapsed += delta;
+}
+
+fn movable_system(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    playfield: Res<Playfield>,
+    mut query: Query<(Entity, &Velocity, &mut Transform, &Movable)>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, velocity, mut transform, movable) in query.iter_mut() {
+        let translation = &mut transform.translation;
+        translation.x += velocity.x * delta * BASE_SPEED;
+        translation.y += velocity.y * delta * BASE_SPEED;
+
+        if movable.auto_despawn {
+            // despawn when out of screen
+            const MARGIN: f32 = 200.;
+            let outside_bottom = translation.y > playfield.h / 2. + MARGIN;
+            let outside_top = translation.y < -playfield.h / 2. - MARGIN;
+            let outside_right = translation.x > playfield.w / 2. + MARGIN;
+            let outside_left = translation.x < -playfield.w / 2. - MARGIN;
+            let outside = outside_bottom || outside_top || outside_right || outside_left;
+
+            if outside {
+                debug!("despawn entity {entity:?}");
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+/// Lasers damage whatever their collider reports hitting (enemies for player lasers,
+/// the player for enemy lasers, bunkers for both), and are consumed by the first hit.
+fn laser_hit_system(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
+    laser_query: Query<&Damage, With<Laser>>,
+    target_query: Query<&Health, Without<Invulnerable>>,
+) {
+    let mut spent_lasers: HashSet<Entity> = HashSet::new();
+    // damage dealt so far this frame, so lasers don't keep hitting a target that is already dead
+    let mut pending_damage: HashMap<Entity, u32> = HashMap::new();
+
+    for event in collision_events.iter() {
+        let laser_hit = matches!(
+            (event.layer, event.other_layer),
+            (Layer::PlayerLaser, Layer::Enemy | Layer::Bunker) | (Layer::EnemyLaser, Layer::Player | Layer::Bunker)
+        );
+        if !laser_hit || spent_lasers.contains(&event.entity) {
+            continue
+        }
+        let (Ok(damage), Ok(health)) = (laser_query.get(event.entity), target_query.get(event.other)) else {
+            continue
+        };
+        let pending = pending_damage.entry(event.other).or_default();
+        if *pending >= health.current {
+            continue
+        }
+
+        // damage target
+        damage_events.send(DamageEvent { target: event.other, amount: damage.0 });
+        *pending += damage.0;
+
+        // remove laser
+        debug!("despawn laser {:?}", event.entity);
+        commands.entity(event.entity).despawn();
+        spent_lasers.insert(event.entity);
+    }
+}
+
+fn player_death_system(
+    mut commands: Commands,
+    mut death_events: EventReader<DeathEvent>,
+    mut player_state: ResMut<PlayerState>,
+    mut score: ResMut<Score>,
+    mut sound_events: EventWriter<SoundEvent>,
+    time: Res<GameTime>,
+    player_query: Query<(), With<Player>>,
+) {
+    for death in death_events.iter() {
+        if player_query.get(death.entity).is_err() {
+            continue
+        }
+        // remove player
+        commands.entity(death.entity).despawn();
+        player_state.shot(time.elapsed_seconds_f64());
+        score.break_combo();
+        sound_events.send(SoundEvent::PlayerDeath);
+
+        // spawn the explosionToSpawn
+        commands.spawn_empty().insert(ExplosionToSpawn(death.translation));
+    }
+}
+
+fn enemy_death_system(
+    mut commands: Commands,
+    mut death_events: EventReader<DeathEvent>,
+    mut enemy_count: ResMut<EnemyCount>,
+    mut score: ResMut<Score>,
+    mut rng: ResMut<GameRng>,
+    time: Res<GameTime>,
+    enemy_query: Query<&Points, With<Enemy>>,
+) {
+    for death in death_events.iter() {
+        let Ok(points) = enemy_query.get(death.entity) else {
+            continue
+        };
+        // remove enemy
+        debug!("despawn enemy {:?}", death.entity);
+        commands.entity(death.entity).despawn();
+        enemy_count.0 -= 1;
+        score.kill(points.0, time.elapsed_seconds_f64());
+
+        // spawn the explosion
+        commands.spawn_empty().insert(ExplosionToSpawn(death.translation));
+
+        // sometimes leave a power-up behind
+        if let Some(kind) = roll_power_up(&mut rng) {
+            commands.spawn(power_up_bundle(kind, death.translation));
+        }
+    }
+}
+
+fn explosion_to_spawn_system(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    mut sound_events: EventWriter<SoundEvent>,
+    query: Query<(Entity, &ExplosionToSpawn)>
+) {
+    for (explosion_entity, explosion_to_spawn) in query.iter() {
+        // spawn the explosion sprite
+        commands
+            .spawn(SpriteSheetBundle {
+                texture_atlas: game_textures.explosion.clone(),
+                transform: Transform {
+                    translation: explosion_to_spawn.0,
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(Explosion)
+            .insert(ExplosionTimer::default());
+        sound_events.send(SoundEvent::Explosion);
+
+        // despawn explosion
+        commands.entity(explosion_entity).despawn();
+    }
+}
+
+fn explosion_animation_system(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    mut query: Query<(Entity, &mut ExplosionTimer, &mut TextureAtlasSprite), With<Explosion>>
+) {
+    for (entity,mut timer, mut sprite) in query.iter_mut() {
+        timer.0.tick(time.delta());
+        if timer.0.finished() {
+            // move to next sprite(s) - several on slow frames
+            sprite.index += timer.0.times_finished_this_tick() as usize;
+            if sprite.index >= EXPLOSION_LEN {
+                commands.entity(entity).despawn()
+            }
+
+        }
+    }
+}
\ No newline at end of file
@@ -1,6 +1,6 @@
-use bevy::{prelude::*, time::FixedTimestep};
+use bevy::prelude::*;
 
-use crate::{GameTextures, WinSize, SPRITE_SCALE, PLAYER_SIZE, components::{Player, Velocity, Movable, FromPlayer, SpriteSize, Laser}, PLAYER_LASER_SIZE, PlayerState, PLAYER_RESPAWN_DELAY};
+use crate::{GameTextures, GameTime, MovementLabel, SPRITE_SCALE, PLAYER_SIZE, components::{Player, Velocity, Movable, SpriteSize, Invulnerable, Health, SpreadShot, RapidFire, Weapon}, RAPID_FIRE_INTERVAL, PLAYER_HEALTH, PlayerState, PLAYER_RESPAWN_DELAY, state::{GameState, despawn_with}, score::Score, highscore::HighScores, collision::{Collider, Layer, PixelPerfect}, playfield::Playfield, controls::{Action, MoveAxis}, sound::SoundEvent, weapon::SPREAD_MUZZLES};
 
 pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
@@ -8,101 +8,145 @@ impl Plugin for PlayerPlugin {
         app
             .insert_resource(PlayerState::default())
             .add_system_set(
-                SystemSet::new()
-                    .with_run_criteria(FixedTimestep::step(0.5))
+                SystemSet::on_enter(GameState::Playing)
+                    .with_system(player_reset_system)
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
                     .with_system(player_spawn_system)
-
+                    .with_system(player_game_over_system)
+                    .with_system(player_keyboad_event_system.before(MovementLabel))
+                    .with_system(player_fire_system)
+                    .with_system(player_invulnerable_system)
             )
-            .add_system(player_keyboad_event_system)
-            .add_system(player_fire_system);
+            .add_system_set(
+                SystemSet::on_exit(GameState::Playing)
+                    .with_system(despawn_with::<Player>)
+            );
     }
 }
 
+fn player_reset_system(mut player_state: ResMut<PlayerState>) {
+    *player_state = PlayerState::default();
+}
+
 fn player_spawn_system(
     mut commands: Commands,
     mut player_state: ResMut<PlayerState>,
-    time: Res<Time>,
+    time: Res<GameTime>,
     game_textures: Res<GameTextures>,
-    win_size: Res<WinSize>
+    playfield: Res<Playfield>
 ) {
     let now = time.elapsed_seconds_f64();
     let last_shot = player_state.last_shot;
 
-    if !player_state.on && (last_shot == -1. || now > last_shot + PLAYER_RESPAWN_DELAY) {
+    if !player_state.on && player_state.lives > 0 && (last_shot == -1. || now > last_shot + PLAYER_RESPAWN_DELAY) {
         // add player
-        let bottom = -win_size.h /2.;
-        let player_pos_y = bottom + PLAYER_SIZE.1 / 2. + 5.;
+        let player_pos_y = player_spawn_y(&playfield);
 
-        commands
-            .spawn(SpriteBundle {
-                texture: game_textures.player.clone(),
-                transform: Transform {
-                    translation: Vec3::new(0., player_pos_y, 10.),
-                    scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.),
-                    ..default()
-                },
+        let mut player = commands.spawn(SpriteBundle {
+            texture: game_textures.player.clone(),
+            transform: Transform {
+                translation: Vec3::new(0., player_pos_y, 10.),
+                scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.),
                 ..default()
-            })
+            },
+            ..default()
+        });
+        player
             .insert(SpriteSize::from(PLAYER_SIZE))
             .insert(Player)
+            .insert(Health::new(PLAYER_HEALTH))
+            .insert(Collider::new(Layer::Player, &[]))
+            .insert(PixelPerfect)
+            .insert(Weapon::player_blaster(&game_textures))
             .insert(Movable { auto_despawn: false })
             .insert(Velocity {
                 x: 0.,
                 y: 0.
             });
+
+        // respawned (not the first ship) - grant a short grace period
+        if last_shot != -1. {
+            player.insert(Invulnerable::default());
+        }
         player_state.spawned()
     }
 }
 
+/// Height of the player ship, just above the bottom of the playfield.
+pub(crate) fn player_spawn_y(playfield: &Playfield) -> f32 {
+    let bottom = -playfield.h /2.;
+    bottom + PLAYER_SIZE.1 / 2. + 5.
+}
+
+fn player_game_over_system(
+    player_state: Res<PlayerState>,
+    mut state: ResMut<State<GameState>>,
+    score: Res<Score>,
+    high_scores: Res<HighScores>,
+    time: Res<GameTime>,
+) {
+    let now = time.elapsed_seconds_f64();
+
+    // out of lives - let the last explosion play out, then end the game
+    if !player_state.on && player_state.lives == 0 && now > player_state.last_shot + PLAYER_RESPAWN_DELAY {
+        let next = if high_scores.qualifies(score.value) {
+            GameState::NameEntry
+        } else {
+            GameState::GameOver
+        };
+        state.set(next).ok();
+    }
+}
+
+fn player_invulnerable_system(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    mut query: Query<(Entity, &mut Invulnerable, &mut Visibility), With<Player>>
+) {
+    for (entity, mut invulnerable, mut visibility) in query.iter_mut() {
+        invulnerable.0.tick(time.delta());
+        if invulnerable.0.finished() {
+            visibility.is_visible = true;
+            commands.entity(entity).remove::<Invulnerable>();
+        } else {
+            // blink while invulnerable
+            visibility.is_visible = (invulnerable.0.elapsed_secs() * 5.).fract() < 0.5;
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
 fn player_fire_system(
     mut commands: Commands,
-    kb: Res<Input<KeyCode>>,
-    game_textures: Res<GameTextures>,
-    query: Query<&Transform, With<Player>>
+    actions: Res<Input<Action>>,
+    time: Res<GameTime>,
+    mut sound_events: EventWriter<SoundEvent>,
+    mut query: Query<(&Transform, &mut Weapon, Option<&SpreadShot>, Option<&RapidFire>), With<Player>>
 ) {
-    if let Ok(player_tf) = query.get_single() {
-        if kb.just_pressed(KeyCode::Space) {
-            let (x,y) = (player_tf.translation.x, player_tf.translation.y);
-            let x_offset = PLAYER_SIZE.0 / 2. * SPRITE_SCALE - 5.;
-            let y_offset = 15.;
-            let mut spawn_laser = |x_offset: f32| {
-                commands.spawn(SpriteBundle {
-                    texture: game_textures.player_laser.clone(),
-                    transform: Transform {
-                        translation: Vec3::new(x + x_offset, y + y_offset, 0.),
-                        scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.),
-                        ..default()
-                    },
-                    ..default()
-                })
-                .insert(Laser)
-                .insert(FromPlayer)
-                .insert(SpriteSize::from(PLAYER_LASER_SIZE))            
-                .insert(Velocity {
-                    x: 0.,
-                    y: 1.
-                })
-                .insert(Movable { auto_despawn: true });    
-            };
-            spawn_laser(x_offset);
-            spawn_laser(-x_offset);
+    if let Ok((player_tf, mut weapon, spread_shot, rapid_fire)) = query.get_single_mut() {
+        // rapid fire always fires while held, at its own (faster) pace
+        let (cooldown, auto_fire) = match rapid_fire {
+            Some(_) => (weapon.cooldown.min(RAPID_FIRE_INTERVAL), true),
+            None => (weapon.cooldown, weapon.auto_fire),
+        };
+        let trigger = actions.just_pressed(Action::Fire) || (auto_fire && actions.pressed(Action::Fire));
+        let now = time.elapsed_seconds_f64();
+
+        if trigger && weapon.ready(now, cooldown) {
+            let muzzles = spread_shot.map(|_| &SPREAD_MUZZLES[..]);
+            weapon.fire(&mut commands, player_tf, muzzles, now);
+            sound_events.send(SoundEvent::PlayerFire);
         }
     }
 }
 
 fn player_keyboad_event_system(
-    kb: Res<Input<KeyCode>>,
+    move_axis: Res<MoveAxis>,
     mut query: Query<&mut Velocity, With<Player>>
 ) {
     if let Ok(mut velocity) = query.get_single_mut() {
-        velocity.x = if kb.pressed(KeyCode::Left) {
-            -1.
-        } else if kb.pressed(KeyCode::Right) {
-            1.
-        } else {
-            0.
-        }
+        velocity.x = move_axis.0;
     }
 }
-
-    
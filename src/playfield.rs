@@ -0,0 +1,74 @@
+use bevy::{prelude::*, render::camera::ScalingMode, window::WindowResized};
+
+use crate::{WinSize, PLAYFIELD_SIZE};
+
+const LETTERBOX_SIZE: f32 = 10_000.; // wide enough for any window aspect
+const LETTERBOX_Z: f32 = 900.;
+
+// region: --- Playfield Resource
+
+/// Logical size of the game area, in world units. Gameplay bounds use it rather than
+/// `WinSize`, and the camera scales it to fit the window (letterboxed), so gameplay is
+/// resolution independent. `WinSize` is still the window, for the UI.
+#[derive(Resource)]
+pub struct Playfield {
+    pub w: f32,
+    pub h: f32,
+}
+
+impl Default for Playfield {
+    fn default() -> Self {
+        Self {
+            w: PLAYFIELD_SIZE.0,
+            h: PLAYFIELD_SIZE.1,
+        }
+    }
+}
+
+// endregion: --- Playfield Resource
+
+pub struct PlayfieldPlugin;
+
+impl Plugin for PlayfieldPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .insert_resource(Playfield::default())
+            .add_startup_system(playfield_setup_system)
+            .add_system(win_size_system);
+    }
+}
+
+fn playfield_setup_system(mut commands: Commands, playfield: Res<Playfield>) {
+    // camera - shows the whole playfield, whatever the window aspect
+    let mut camera = Camera2dBundle::default();
+    camera.projection.scaling_mode = ScalingMode::Auto { min_width: playfield.w, min_height: playfield.h };
+    commands.spawn(camera);
+
+    // letterbox - bars hiding what the camera shows around the playfield, over every sprite
+    let (w, h) = (playfield.w, playfield.h);
+    let bars = [
+        (Vec2::new(-(w + LETTERBOX_SIZE) / 2., 0.), Vec2::new(LETTERBOX_SIZE, h + 2. * LETTERBOX_SIZE)),
+        (Vec2::new((w + LETTERBOX_SIZE) / 2., 0.), Vec2::new(LETTERBOX_SIZE, h + 2. * LETTERBOX_SIZE)),
+        (Vec2::new(0., -(h + LETTERBOX_SIZE) / 2.), Vec2::new(w, LETTERBOX_SIZE)),
+        (Vec2::new(0., (h + LETTERBOX_SIZE) / 2.), Vec2::new(w, LETTERBOX_SIZE)),
+    ];
+    for (position, size) in bars {
+        commands.spawn(SpriteBundle {
+            sprite: Sprite {
+                color: Color::BLACK,
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: Transform::from_translation(position.extend(LETTERBOX_Z)),
+            ..default()
+        });
+    }
+}
+
+/// Keep WinSize in sync with the primary window.
+fn win_size_system(mut resized_events: EventReader<WindowResized>, mut win_size: ResMut<WinSize>) {
+    for event in resized_events.iter().filter(|event| event.id.is_primary()) {
+        win_size.w = event.width;
+        win_size.h = event.height;
+    }
+}
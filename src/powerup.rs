@@ -0,0 +1,153 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{GameTime, PLAYER_MAX_LIVES, POWER_UP_DROP_CHANCE, PlayerState, components::{Movable, Player, PowerUp, PowerUpKind, RapidFire, Shield, SpreadShot, SpriteSize, Velocity}, collision::{Collider, CollisionEvent, CollisionLabel, Layer}, rng::GameRng, state::{GameState, despawn_with}};
+
+const POWER_UP_SIZE: f32 = 18.;
+const POWER_UP_FALL_SPEED: f32 = 0.4; // of BASE_SPEED
+const SHIELD_COLOR: Color = Color::rgb(0.5, 0.9, 1.);
+
+impl PowerUpKind {
+    fn color(&self) -> Color {
+        match self {
+            PowerUpKind::SpreadShot => Color::ORANGE,
+            PowerUpKind::RapidFire => Color::YELLOW,
+            PowerUpKind::Shield => SHIELD_COLOR,
+            PowerUpKind::ExtraLife => Color::LIME_GREEN,
+        }
+    }
+}
+
+pub struct PowerUpPlugin;
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(power_up_collect_system.after(CollisionLabel))
+                    .with_system(power_up_expiry_system::<SpreadShot>)
+                    .with_system(power_up_expiry_system::<RapidFire>)
+                    .with_system(power_up_expiry_system::<Shield>)
+                    .with_system(shield_tint_system)
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::Playing)
+                    .with_system(despawn_with::<PowerUp>)
+            );
+    }
+}
+
+/// Whether a destroyed enemy drops a power-up, and which one (weighted by `PowerUpKind::weight`).
+pub(crate) fn roll_power_up(rng: &mut GameRng) -> Option<PowerUpKind> {
+    if !rng.gen_bool(POWER_UP_DROP_CHANCE) {
+        return None;
+    }
+    let total: u32 = PowerUpKind::ALL.iter().map(|kind| kind.weight()).sum();
+    let mut roll = rng.gen_range(0..total);
+    PowerUpKind::ALL.into_iter().find(|kind| {
+        let hit = roll < kind.weight();
+        roll = roll.saturating_sub(kind.weight());
+        hit
+    })
+}
+
+/// Falling pickup of `kind`, dropped at `translation`.
+pub fn power_up_bundle(kind: PowerUpKind, translation: Vec3) -> (SpriteBundle, PowerUp, SpriteSize, Collider, Movable, Velocity) {
+    (
+        SpriteBundle {
+            sprite: Sprite {
+                color: kind.color(),
+                custom_size: Some(Vec2::splat(POWER_UP_SIZE)),
+                ..default()
+            },
+            transform: Transform::from_translation(translation.truncate().extend(5.)),
+            ..default()
+        },
+        PowerUp(kind),
+        SpriteSize::from((POWER_UP_SIZE, POWER_UP_SIZE)),
+        Collider::new(Layer::PowerUp, &[Layer::Player]),
+        Movable { auto_despawn: true },
+        Velocity { x: 0., y: -POWER_UP_FALL_SPEED },
+    )
+}
+
+/// Picking up a power-up (re)starts its timer, or grants a life right away.
+fn power_up_collect_system(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut player_state: ResMut<PlayerState>,
+    power_up_query: Query<&PowerUp>,
+) {
+    let mut collected: HashSet<Entity> = HashSet::new();
+
+    for event in collision_events.iter() {
+        if (event.layer, event.other_layer) != (Layer::PowerUp, Layer::Player) || collected.contains(&event.entity) {
+            continue
+        }
+        let Ok(power_up) = power_up_query.get(event.entity) else {
+            continue
+        };
+        let mut player = commands.entity(event.other);
+        match power_up.0 {
+            PowerUpKind::SpreadShot => { player.insert(SpreadShot::default()); }
+            PowerUpKind::RapidFire => { player.insert(RapidFire::default()); }
+            PowerUpKind::Shield => { player.insert(Shield::default()); }
+            PowerUpKind::ExtraLife => player_state.lives = (player_state.lives + 1).min(PLAYER_MAX_LIVES),
+        }
+
+        commands.entity(event.entity).despawn();
+        collected.insert(event.entity);
+    }
+}
+
+/// Timed power-ups, on the player ship.
+pub trait PowerUpTimer: Component {
+    fn timer(&self) -> &Timer;
+    fn timer_mut(&mut self) -> &mut Timer;
+
+    /// Whole seconds left, as shown in the HUD.
+    fn seconds_left(&self) -> u32 {
+        self.timer().remaining_secs().ceil() as u32
+    }
+}
+
+macro_rules! impl_power_up_timer {
+    ($($component:ty),*) => {
+        $(impl PowerUpTimer for $component {
+            fn timer(&self) -> &Timer {
+                &self.0
+            }
+
+            fn timer_mut(&mut self) -> &mut Timer {
+                &mut self.0
+            }
+        })*
+    };
+}
+
+impl_power_up_timer!(SpreadShot, RapidFire, Shield);
+
+fn power_up_expiry_system<T: PowerUpTimer>(
+    mut commands: Commands,
+    time: Res<GameTime>,
+    mut query: Query<(Entity, &mut T)>,
+) {
+    for (entity, mut power_up) in query.iter_mut() {
+        if power_up.timer_mut().tick(time.delta()).finished() {
+            commands.entity(entity).remove::<T>();
+        }
+    }
+}
+
+/// Shielded ships are tinted, so the player sees the shield is up.
+fn shield_tint_system(mut query: Query<(&mut Sprite, Option<&Shield>), With<Player>>) {
+    for (mut sprite, shield) in query.iter_mut() {
+        let color = if shield.is_some() { SHIELD_COLOR } else { Color::WHITE };
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
@@ -0,0 +1,361 @@
+use std::{fs, path::PathBuf, time::Duration};
+
+use bevy::{prelude::*, asset::LoadState, input::{keyboard::KeyboardInput, gamepad::{GamepadEventRaw, GamepadInfo}, ButtonState}, time::{TimeSystem, TimeUpdateStrategy}};
+use serde::{Deserialize, Serialize};
+
+use crate::{GameTextures, collision::AlphaMasks, controls::Bindings, enemy::wave::{WaveConfig, WaveDefinitions}, rng::GameRng, score::Score, settings::Settings, state::GameState};
+
+const REPLAY_ARG: &str = "--replay";
+const LAST_REPLAY_FILE: &str = "last.replay.ron";
+
+// region: --- Replay File
+
+/// One game, from entering Playing until leaving it. Together with the seed, the frame
+/// durations, key and gamepad events (and the key bindings interpreting them) are all the
+/// simulation depends on, so playing them back reproduces the game exactly.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Replay {
+    seed: u64,
+    score: u32, // final score, checked at the end of the playback
+    deltas: Vec<u64>, // frame durations, in nanoseconds
+    keys: Vec<(usize, KeyCode, bool)>, // frame, key, pressed (false for released), in event order
+    #[serde(default)]
+    gamepad: Vec<(usize, GamepadEventRaw)>, // frame, event, in event order
+    #[serde(default)]
+    bindings: Bindings,
+}
+
+impl Replay {
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    fn load(path: &str) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|err| err.to_string())?;
+        ron::from_str(&content).map_err(|err| err.to_string())
+    }
+
+    fn save(&self) {
+        let Some(path) = last_replay_path() else {
+            return;
+        };
+        let content = match ron::to_string(self) {
+            Ok(content) => content,
+            Err(err) => return warn!("cannot serialize replay ({err})"),
+        };
+        match path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|_| fs::write(&path, content)) {
+            Ok(_) => info!("replay saved to {path:?} (play back with {REPLAY_ARG} <file>)"),
+            Err(err) => warn!("cannot save replay to {path:?} ({err})"),
+        }
+    }
+
+    fn frame_keys(&self, frame: usize) -> impl Iterator<Item = &(usize, KeyCode, bool)> {
+        let start = self.keys.partition_point(|key| key.0 < frame);
+        self.keys[start..].iter().take_while(move |key| key.0 == frame)
+    }
+
+    fn frame_gamepad(&self, frame: usize) -> impl Iterator<Item = &GamepadEventRaw> {
+        let start = self.gamepad.partition_point(|event| event.0 < frame);
+        self.gamepad[start..].iter().take_while(move |event| event.0 == frame).map(|event| &event.1)
+    }
+}
+
+fn last_replay_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(env!("CARGO_PKG_NAME")).join(LAST_REPLAY_FILE))
+}
+
+// endregion: --- Replay File
+
+// region: --- Replay Resources
+
+/// Game being recorded - every game is, and saved as the last replay when it ends.
+#[derive(Resource, Default)]
+pub struct Recorder {
+    replay: Option<Replay>,
+    last: Option<Replay>,
+    finished: bool, // left Playing this frame, saved once the frame is recorded
+    save: bool,
+}
+
+impl Recorder {
+    /// Last finished game.
+    pub fn last(&self) -> Option<&Replay> {
+        self.last.as_ref()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum PlaybackPhase {
+    Loading, // waiting for the assets the simulation depends on
+    Ready,
+    Frame(usize),
+    Done,
+}
+
+/// Replay being played back (`--replay <file>` on the command line).
+#[derive(Resource)]
+pub struct Playback {
+    replay: Replay,
+    phase: PlaybackPhase,
+}
+
+impl Playback {
+    pub fn is_done(&self) -> bool {
+        self.phase == PlaybackPhase::Done
+    }
+}
+
+// endregion: --- Replay Resources
+
+/// Records every game, or plays a recorded one back instead.
+pub struct ReplayPlugin {
+    playback: Option<Replay>,
+    save: bool, // finished games as the last replay file
+}
+
+impl ReplayPlugin {
+    /// Play back `--replay <file>` if given on the command line, else record and save every game.
+    pub fn from_args() -> Self {
+        Self {
+            playback: replay_from_args(),
+            save: true,
+        }
+    }
+
+    /// Record every game without saving it, the last one is kept by the `Recorder`.
+    pub fn recording() -> Self {
+        Self {
+            playback: None,
+            save: false,
+        }
+    }
+
+    pub fn playback(replay: Replay) -> Self {
+        Self {
+            playback: Some(replay),
+            save: false,
+        }
+    }
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        let Some(replay) = self.playback.clone() else {
+            app
+                .insert_resource(Recorder {
+                    save: self.save,
+                    ..default()
+                })
+                .add_system_set(
+                    SystemSet::on_enter(GameState::Playing)
+                        .with_system(record_start_system)
+                )
+                .add_system_set(
+                    SystemSet::on_exit(GameState::Playing)
+                        .with_system(record_stop_system)
+                )
+                .add_system_to_stage(CoreStage::Last, record_frame_system);
+            return;
+        };
+
+        // the replay seed wins over --seed
+        app
+            .insert_resource(GameRng::new(replay.seed))
+            .insert_resource(Playback {
+                replay,
+                phase: PlaybackPhase::Loading,
+            })
+            .add_system_to_stage(CoreStage::First, playback_input_system.after(TimeSystem))
+            .add_system_set(
+                SystemSet::on_update(GameState::Title)
+                    .with_system(playback_start_system)
+            )
+            .add_system_to_stage(CoreStage::Last, playback_ready_system.before(playback_clock_system))
+            .add_system_to_stage(CoreStage::Last, playback_clock_system);
+    }
+}
+
+fn replay_from_args() -> Option<Replay> {
+    let path = std::env::args().skip_while(|arg| arg != REPLAY_ARG).nth(1)?;
+    match Replay::load(&path) {
+        Ok(replay) => {
+            info!("replaying {path} (seed {}, {} frames)", replay.seed, replay.deltas.len());
+            Some(replay)
+        }
+        Err(err) => {
+            warn!("cannot load replay {path} ({err}), playing normally");
+            None
+        }
+    }
+}
+
+// region: --- Recording
+
+fn record_start_system(
+    mut recorder: ResMut<Recorder>,
+    rng: Res<GameRng>,
+    settings: Res<Settings>,
+    kb: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+) {
+    // keys already held when the game starts (the start key itself was consumed)
+    let keys = kb.get_pressed().map(|key| (0, *key, true)).collect();
+
+    // same for the gamepads, connected with their stick and held buttons
+    let mut gamepad = Vec::new();
+    for pad in gamepads.iter() {
+        let name = gamepads.name(pad).unwrap_or_default().to_string();
+        let mut events = vec![GamepadEventType::Connected(GamepadInfo { name })];
+        if let Some(value) = axes.get(GamepadAxis::new(pad, GamepadAxisType::LeftStickX)) {
+            events.push(GamepadEventType::AxisChanged(GamepadAxisType::LeftStickX, value));
+        }
+        let held = buttons.get_pressed().filter(|button| button.gamepad == pad);
+        events.extend(held.map(|button| GamepadEventType::ButtonChanged(button.button_type, 1.)));
+        gamepad.extend(events.into_iter().map(|event_type| (0, GamepadEventRaw::new(pad, event_type))));
+    }
+
+    recorder.replay = Some(Replay {
+        seed: rng.seed(),
+        keys,
+        gamepad,
+        bindings: settings.bindings.clone(),
+        ..default()
+    });
+}
+
+fn record_stop_system(mut recorder: ResMut<Recorder>) {
+    recorder.finished = true;
+}
+
+fn record_frame_system(
+    mut recorder: ResMut<Recorder>,
+    mut key_events: EventReader<KeyboardInput>,
+    mut gamepad_events: EventReader<GamepadEventRaw>,
+    time: Res<Time>,
+    score: Res<Score>,
+) {
+    let recorder = &mut *recorder;
+    let Some(replay) = &mut recorder.replay else {
+        key_events.clear();
+        gamepad_events.clear();
+        return;
+    };
+
+    // the first frame's keys and gamepad events are the ones held at start
+    let frame = replay.deltas.len();
+    if frame > 0 {
+        for event in key_events.iter() {
+            if let Some(key) = event.key_code {
+                replay.keys.push((frame, key, event.state == ButtonState::Pressed));
+            }
+        }
+        replay.gamepad.extend(gamepad_events.iter().map(|event| (frame, event.clone())));
+    } else {
+        key_events.clear();
+        gamepad_events.clear();
+    }
+    replay.deltas.push(time.delta().as_nanos() as u64);
+
+    if recorder.finished {
+        replay.score = score.value;
+        if recorder.save {
+            replay.save();
+        }
+        recorder.last = recorder.replay.take();
+        recorder.finished = false;
+    }
+}
+
+// endregion: --- Recording
+
+// region: --- Playback
+
+/// Replace this frame's key and gamepad events by the recorded ones.
+fn playback_input_system(
+    playback: Res<Playback>,
+    mut key_events: ResMut<Events<KeyboardInput>>,
+    mut gamepad_events: ResMut<Events<GamepadEventRaw>>,
+) {
+    let PlaybackPhase::Frame(frame) = playback.phase else {
+        return;
+    };
+    key_events.clear();
+    for &(_, key, pressed) in playback.replay.frame_keys(frame) {
+        key_events.send(KeyboardInput {
+            scan_code: 0,
+            key_code: Some(key),
+            state: if pressed { ButtonState::Pressed } else { ButtonState::Released },
+        });
+    }
+    gamepad_events.clear();
+    gamepad_events.extend(playback.replay.frame_gamepad(frame).cloned());
+}
+
+/// Start the game with the recorded bindings (not saved, the settings file is untouched).
+fn playback_start_system(playback: Res<Playback>, mut state: ResMut<State<GameState>>, mut settings: ResMut<Settings>) {
+    if playback.phase == PlaybackPhase::Frame(0) {
+        settings.bindings = playback.replay.bindings.clone();
+        state.set(GameState::Playing).ok();
+    }
+}
+
+/// Wait for the wave files and sprite masks, which the recorded game had
+/// (a texture that failed to load has no mask, in the recording either).
+fn playback_ready_system(
+    mut playback: ResMut<Playback>,
+    asset_server: Res<AssetServer>,
+    wave_definitions: Res<WaveDefinitions>,
+    wave_configs: Res<Assets<WaveConfig>>,
+    game_textures: Res<GameTextures>,
+    alpha_masks: Res<AlphaMasks>,
+) {
+    if playback.phase != PlaybackPhase::Loading {
+        return;
+    }
+    let textures = [
+        &game_textures.player,
+        &game_textures.enemy_grunt,
+        &game_textures.enemy_tank,
+        &game_textures.enemy_diver,
+        &game_textures.enemy_shooter,
+    ];
+    let masks_loaded = textures.iter().all(|texture| {
+        alpha_masks.get(texture).is_some() || asset_server.get_load_state(*texture) == LoadState::Failed
+    });
+    if masks_loaded && wave_definitions.is_loaded(&wave_configs, &asset_server) {
+        playback.phase = PlaybackPhase::Ready;
+    }
+}
+
+/// Drive `Time` from the recorded frame durations, one frame ahead.
+fn playback_clock_system(
+    mut playback: ResMut<Playback>,
+    mut time_strategy: ResMut<TimeUpdateStrategy>,
+    time: Res<Time>,
+    score: Res<Score>,
+) {
+    let next = match playback.phase {
+        PlaybackPhase::Ready => 0,
+        PlaybackPhase::Frame(frame) => frame + 1,
+        PlaybackPhase::Loading | PlaybackPhase::Done => return,
+    };
+
+    if let Some(&delta) = playback.replay.deltas.get(next) {
+        let last_update = time.last_update().unwrap_or_else(|| time.startup());
+        *time_strategy = TimeUpdateStrategy::ManualInstant(last_update + Duration::from_nanos(delta));
+        playback.phase = PlaybackPhase::Frame(next);
+    } else {
+        *time_strategy = TimeUpdateStrategy::Automatic;
+        playback.phase = PlaybackPhase::Done;
+        let verdict = if score.value == playback.replay.score { "match" } else { "MISMATCH" };
+        info!("replay done, score {} - recorded {} ({verdict})", score.value, playback.replay.score);
+    }
+}
+
+// endregion: --- Playback
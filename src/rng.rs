@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+use rand::{rngs::StdRng, thread_rng, Rng, RngCore, SeedableRng};
+
+use crate::{settings::Settings, state::GameState};
+
+const SEED_ARG: &str = "--seed";
+
+// region: --- Game Rng
+
+/// Single source of randomness for the simulation - every random decision goes through it,
+/// so that a game is reproducible from its seed (`--seed <u64>` on the command line,
+/// or `seed` in the settings file).
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Restart the sequence from the seed.
+    pub fn reset(&mut self) {
+        self.rng = StdRng::seed_from_u64(self.seed);
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+// endregion: --- Game Rng
+
+pub struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        let seed = seed_from_args()
+            .or_else(|| app.world.get_resource::<Settings>().and_then(|settings| settings.seed))
+            .unwrap_or_else(|| thread_rng().gen());
+        info!("rng seed {seed} (replay with {SEED_ARG} {seed})");
+
+        app
+            .insert_resource(GameRng::new(seed))
+            .add_system_set(
+                SystemSet::on_enter(GameState::Playing)
+                    .with_system(rng_reset_system)
+            );
+    }
+}
+
+/// Every game plays out the same sequence for a given seed.
+fn rng_reset_system(mut rng: ResMut<GameRng>) {
+    rng.reset();
+}
+
+fn seed_from_args() -> Option<u64> {
+    let mut args = std::env::args().skip_while(|arg| arg != SEED_ARG).skip(1);
+    let value = args.next()?;
+    match value.parse() {
+        Ok(seed) => Some(seed),
+        Err(err) => {
+            warn!("invalid {SEED_ARG} '{value}' ({err}), using a random seed");
+            None
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+
+use crate::{GameTime, state::GameState, COMBO_WINDOW, COMBO_MAX_MULTIPLIER};
+
+// region: --- Score Resource
+
+#[derive(Resource, Default)]
+pub struct Score {
+    pub value: u32,
+    pub combo: u32, // kills chained within COMBO_WINDOW, 0 if none
+    last_kill: f64,
+}
+impl Score {
+    pub fn multiplier(&self) -> u32 {
+        self.combo.clamp(1, COMBO_MAX_MULTIPLIER)
+    }
+
+    /// Award `points` for a kill at `time`, returns the points gained after the combo multiplier.
+    pub fn kill(&mut self, points: u32, time: f64) -> u32 {
+        self.combo += 1;
+        self.last_kill = time;
+        let gained = points * self.multiplier();
+        self.value += gained;
+        gained
+    }
+
+    pub fn break_combo(&mut self) {
+        self.combo = 0;
+    }
+}
+
+// endregion: --- Score Resource
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .insert_resource(Score::default())
+            .add_system_set(
+                SystemSet::on_enter(GameState::Playing)
+                    .with_system(score_reset_system)
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Playing)
+                    .with_system(score_combo_system)
+            );
+    }
+}
+
+fn score_reset_system(mut score: ResMut<Score>) {
+    *score = Score::default();
+}
+
+fn score_combo_system(time: Res<GameTime>, mut score: ResMut<Score>) {
+    if score.combo > 0 && time.elapsed_seconds_f64() > score.last_kill + COMBO_WINDOW {
+        score.break_combo();
+    }
+}
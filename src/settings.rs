@@ -0,0 +1,335 @@
+use std::{fs, path::PathBuf};
+
+use bevy::{prelude::*, window::{PresentMode, WindowMode}};
+use serde::{Deserialize, Serialize};
+
+use crate::{GameFonts, PLAYFIELD_SIZE, controls::{Action, Bindings}, state::{GameState, despawn_with, spawn_screen}};
+
+const SETTINGS_FILE: &str = "settings.toml";
+const RESOLUTIONS: [(u32, u32); 6] = [(598, 676), (800, 900), (1024, 768), (1280, 720), (1600, 900), (1920, 1080)];
+
+// region: --- Settings Resource
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayMode {
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+/// User settings, persisted as TOML in the user config directory.
+/// Loaded before the app is built, as the window is created from them.
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub resolution: (u32, u32), // window size, in logical pixels
+    pub mode: DisplayMode,
+    pub vsync: bool,
+    pub master_volume: f32, // 0 to 1, scales both channels
+    pub music_volume: f32, // 0 to 1
+    pub sfx_volume: f32, // 0 to 1
+    pub muted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>, // fixed game seed, `--seed` on the command line wins
+    pub bindings: Bindings, // last, as TOML tables go after plain values
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            resolution: (PLAYFIELD_SIZE.0 as u32, PLAYFIELD_SIZE.1 as u32),
+            mode: DisplayMode::Windowed,
+            vsync: true,
+            master_volume: 1.,
+            music_volume: 0.6,
+            sfx_volume: 1.,
+            muted: false,
+            seed: None,
+            bindings: Bindings::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Load the settings from disk, falling back to the defaults if missing or invalid.
+    pub fn load() -> Self {
+        let Some(path) = settings_path() else {
+            return Self::default();
+        };
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(_) => return Self::default(),
+        };
+        match toml::from_str::<Settings>(&content) {
+            Ok(settings) => settings,
+            Err(err) => {
+                warn!("invalid settings file {path:?} ({err}), using defaults");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        let Some(path) = settings_path() else {
+            return;
+        };
+        let content = match toml::to_string(self) {
+            Ok(content) => content,
+            Err(err) => return warn!("cannot serialize settings ({err})"),
+        };
+        if let Err(err) = path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|_| fs::write(&path, content)) {
+            warn!("cannot save settings to {path:?} ({err})");
+        }
+    }
+
+    /// Effective music volume, mixed with the master volume and mute.
+    pub fn music_volume(&self) -> f32 {
+        if self.muted { 0. } else { self.master_volume * self.music_volume }
+    }
+
+    /// Effective sound effects volume, mixed with the master volume and mute.
+    pub fn sfx_volume(&self) -> f32 {
+        if self.muted { 0. } else { self.master_volume * self.sfx_volume }
+    }
+
+    pub fn window_descriptor(&self) -> WindowDescriptor {
+        WindowDescriptor {
+            width: self.resolution.0 as f32,
+            height: self.resolution.1 as f32,
+            mode: self.window_mode(),
+            present_mode: self.present_mode(),
+            ..default()
+        }
+    }
+
+    /// Apply to an existing window, when changed from the options menu.
+    fn apply(&self, window: &mut Window) {
+        window.set_resolution(self.resolution.0 as f32, self.resolution.1 as f32);
+        window.set_mode(self.window_mode());
+        window.set_present_mode(self.present_mode());
+    }
+
+    fn window_mode(&self) -> WindowMode {
+        match self.mode {
+            DisplayMode::Windowed => WindowMode::Windowed,
+            DisplayMode::Borderless => WindowMode::BorderlessFullscreen,
+            DisplayMode::Fullscreen => WindowMode::SizedFullscreen,
+        }
+    }
+
+    fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
+    }
+}
+
+fn settings_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(env!("CARGO_PKG_NAME")).join(SETTINGS_FILE))
+}
+
+// endregion: --- Settings Resource
+
+// region: --- Options Menu Components
+
+#[derive(Component)]
+struct OptionsScreen;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum OptionItem {
+    Resolution,
+    Mode,
+    VSync,
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    Sound,
+    Binding(Action),
+}
+
+const OPTION_ITEMS: [OptionItem; 11] = [
+    OptionItem::Resolution,
+    OptionItem::Mode,
+    OptionItem::VSync,
+    OptionItem::MasterVolume,
+    OptionItem::MusicVolume,
+    OptionItem::SfxVolume,
+    OptionItem::Sound,
+    OptionItem::Binding(Action::MoveLeft),
+    OptionItem::Binding(Action::MoveRight),
+    OptionItem::Binding(Action::Fire),
+    OptionItem::Binding(Action::Pause),
+];
+
+#[derive(Resource, Default)]
+struct OptionsSelection {
+    index: usize, // in `OPTION_ITEMS`
+    rebinding: bool, // waiting for the new key of the selected binding
+}
+
+// endregion: --- Options Menu Components
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // normally inserted before the app is built (see `Settings::load`)
+            .init_resource::<Settings>()
+            .init_resource::<OptionsSelection>()
+            .add_system_set(
+                SystemSet::on_enter(GameState::Options)
+                    .with_system(options_screen_system)
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Options)
+                    .with_system(options_input_system)
+                    .with_system(options_text_system.after(options_input_system))
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::Options)
+                    .with_system(despawn_with::<OptionsScreen>)
+                    .with_system(options_save_system)
+            );
+    }
+}
+
+fn options_screen_system(mut commands: Commands, mut selection: ResMut<OptionsSelection>, fonts: Res<GameFonts>) {
+    *selection = OptionsSelection::default();
+
+    let lines = [
+        ("OPTIONS", 56.),
+        ("Up / Down to select, Left / Right to change", 18.),
+        ("Enter to rebind a key", 18.),
+        ("Esc to go back", 18.),
+    ];
+    let screen = spawn_screen(&mut commands, &fonts.main, &lines);
+    commands.entity(screen).insert(OptionsScreen).with_children(|parent| {
+        for item in OPTION_ITEMS {
+            let style = TextStyle {
+                font: fonts.main.clone(),
+                font_size: 28.,
+                color: Color::WHITE,
+            };
+            parent.spawn(TextBundle::from_section("", style)).insert(item);
+        }
+    });
+}
+
+fn options_input_system(
+    mut kb: ResMut<Input<KeyCode>>,
+    mut state: ResMut<State<GameState>>,
+    mut selection: ResMut<OptionsSelection>,
+    mut settings: ResMut<Settings>,
+    mut windows: Option<ResMut<Windows>>,
+) {
+    let item = OPTION_ITEMS[selection.index];
+
+    // the next key pressed becomes the binding, Esc cancels
+    if selection.rebinding {
+        let key = kb.get_just_pressed().next().copied();
+        if let (OptionItem::Binding(action), Some(key)) = (item, key) {
+            if key != KeyCode::Escape {
+                settings.bindings.rebind(action, key);
+            }
+            kb.reset(key);
+            selection.rebinding = false;
+        }
+        return;
+    }
+
+    if kb.just_pressed(KeyCode::Up) {
+        selection.index = (selection.index + OPTION_ITEMS.len() - 1) % OPTION_ITEMS.len();
+    } else if kb.just_pressed(KeyCode::Down) {
+        selection.index = (selection.index + 1) % OPTION_ITEMS.len();
+    }
+
+    let step: i32 = if kb.just_pressed(KeyCode::Left) {
+        -1
+    } else if kb.just_pressed(KeyCode::Right) {
+        1
+    } else {
+        0
+    };
+    if step != 0 {
+        change_option(&mut settings, item, step);
+        if let Some(window) = windows.as_mut().and_then(|windows| windows.get_primary_mut()) {
+            settings.apply(window);
+        }
+    }
+
+    if kb.just_pressed(KeyCode::Return) && matches!(item, OptionItem::Binding(_)) {
+        kb.reset(KeyCode::Return);
+        selection.rebinding = true;
+    } else if kb.just_pressed(KeyCode::Escape) {
+        kb.reset(KeyCode::Escape);
+        state.set(GameState::Title).ok();
+    }
+}
+
+/// Move the `item` setting `step` choices forward (or back, if negative), wrapping around.
+fn change_option(settings: &mut Settings, item: OptionItem, step: i32) {
+    let cycle = |index: usize, len: usize| (index as i32 + step).rem_euclid(len as i32) as usize;
+    match item {
+        OptionItem::Resolution => {
+            let index = RESOLUTIONS.iter().position(|resolution| *resolution == settings.resolution);
+            // a custom resolution (edited in the file) starts the cycle over
+            settings.resolution = RESOLUTIONS[index.map_or(0, |index| cycle(index, RESOLUTIONS.len()))];
+        }
+        OptionItem::Mode => {
+            let modes = [DisplayMode::Windowed, DisplayMode::Borderless, DisplayMode::Fullscreen];
+            let index = modes.iter().position(|mode| *mode == settings.mode).unwrap_or(0);
+            settings.mode = modes[cycle(index, modes.len())];
+        }
+        OptionItem::VSync => settings.vsync = !settings.vsync,
+        OptionItem::MasterVolume => settings.master_volume = step_volume(settings.master_volume, step),
+        OptionItem::MusicVolume => settings.music_volume = step_volume(settings.music_volume, step),
+        OptionItem::SfxVolume => settings.sfx_volume = step_volume(settings.sfx_volume, step),
+        OptionItem::Sound => settings.muted = !settings.muted,
+        OptionItem::Binding(_) => (), // rebound with Enter
+    }
+}
+
+/// Volumes go by tenths, from 0 to 1.
+fn step_volume(volume: f32, step: i32) -> f32 {
+    ((volume * 10.).round() as i32 + step).clamp(0, 10) as f32 / 10.
+}
+
+fn options_text_system(
+    settings: Res<Settings>,
+    selection: Res<OptionsSelection>,
+    added_query: Query<(), Added<OptionItem>>,
+    mut query: Query<(&mut Text, &OptionItem)>,
+) {
+    if !settings.is_changed() && !selection.is_changed() && added_query.is_empty() {
+        return;
+    }
+
+    for (mut text, item) in query.iter_mut() {
+        let (label, value) = match item {
+            OptionItem::Resolution => ("Resolution", format!("{} x {}", settings.resolution.0, settings.resolution.1)),
+            OptionItem::Mode => ("Mode", format!("{:?}", settings.mode)),
+            OptionItem::VSync => ("VSync", if settings.vsync { "On" } else { "Off" }.to_string()),
+            OptionItem::MasterVolume => ("Master volume", format!("{:.0}%", settings.master_volume * 100.)),
+            OptionItem::MusicVolume => ("Music volume", format!("{:.0}%", settings.music_volume * 100.)),
+            OptionItem::SfxVolume => ("Effects volume", format!("{:.0}%", settings.sfx_volume * 100.)),
+            OptionItem::Sound => ("Sound (M)", if settings.muted { "Muted" } else { "On" }.to_string()),
+            OptionItem::Binding(action) => (action.label(), settings.bindings.describe(*action)),
+        };
+        let selected = OPTION_ITEMS[selection.index] == *item;
+        text.sections[0].value = match item {
+            OptionItem::Binding(_) if selected && selection.rebinding => format!("{label}  [press a key]"),
+            OptionItem::Binding(_) => format!("{label}  {value}"),
+            _ => format!("{label}  < {value} >"),
+        };
+        text.sections[0].style.color = if selected { Color::YELLOW } else { Color::WHITE };
+    }
+}
+
+/// Write the settings back when leaving the menu.
+fn options_save_system(settings: Res<Settings>) {
+    settings.save();
+}
@@ -0,0 +1,121 @@
+use bevy::{prelude::*, audio::AudioSink};
+
+use crate::{GameSounds, settings::Settings, state::GameState};
+
+const MUTE_KEY: KeyCode = KeyCode::M;
+
+// region: --- Sound Events
+
+/// Sent by the gameplay systems, one per sound effect to play.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundEvent {
+    PlayerFire,
+    EnemyFire,
+    Explosion,
+    PlayerDeath,
+}
+
+// endregion: --- Sound Events
+
+/// Background music sink, while a game is on.
+#[derive(Resource, Default)]
+struct Music(Option<Handle<AudioSink>>);
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<SoundEvent>()
+            .init_resource::<Music>()
+            .add_system(sound_effect_system)
+            .add_system(music_volume_system)
+            .add_system(mute_toggle_system)
+            .add_system_set(
+                SystemSet::on_enter(GameState::Playing)
+                    .with_system(music_start_system)
+            )
+            .add_system_set(
+                SystemSet::on_pause(GameState::Playing)
+                    .with_system(music_pause_system)
+            )
+            .add_system_set(
+                SystemSet::on_resume(GameState::Playing)
+                    .with_system(music_resume_system)
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::Playing)
+                    .with_system(music_stop_system)
+            );
+    }
+}
+
+fn sound_effect_system(
+    mut sound_events: EventReader<SoundEvent>,
+    audio: Res<Audio>,
+    game_sounds: Res<GameSounds>,
+    settings: Res<Settings>,
+) {
+    let volume = settings.sfx_volume();
+    for event in sound_events.iter() {
+        if volume > 0. {
+            audio.play_with_settings(game_sounds.effect(*event), PlaybackSettings::ONCE.with_volume(volume));
+        }
+    }
+}
+
+// region: --- Music
+
+fn music_start_system(
+    mut music: ResMut<Music>,
+    audio: Res<Audio>,
+    audio_sinks: Res<Assets<AudioSink>>,
+    game_sounds: Res<GameSounds>,
+    settings: Res<Settings>,
+) {
+    let sink = audio.play_with_settings(game_sounds.music.clone(), PlaybackSettings::LOOP.with_volume(settings.music_volume()));
+    // keep a strong handle, or the sink is dropped (and the music stopped) right away
+    music.0 = Some(audio_sinks.get_handle(sink));
+}
+
+fn music_pause_system(music: Res<Music>, audio_sinks: Res<Assets<AudioSink>>) {
+    if let Some(sink) = music.0.as_ref().and_then(|handle| audio_sinks.get(handle)) {
+        sink.pause();
+    }
+}
+
+fn music_resume_system(music: Res<Music>, audio_sinks: Res<Assets<AudioSink>>) {
+    if let Some(sink) = music.0.as_ref().and_then(|handle| audio_sinks.get(handle)) {
+        sink.play();
+    }
+}
+
+fn music_stop_system(mut music: ResMut<Music>, audio_sinks: Res<Assets<AudioSink>>) {
+    if let Some(sink) = music.0.take().and_then(|handle| audio_sinks.get(&handle)) {
+        sink.stop();
+    }
+}
+
+/// Follow volume changes from the options menu and the mute key.
+fn music_volume_system(music: Res<Music>, audio_sinks: Res<Assets<AudioSink>>, settings: Res<Settings>) {
+    if !settings.is_changed() && !music.is_changed() {
+        return;
+    }
+    if let Some(sink) = music.0.as_ref().and_then(|handle| audio_sinks.get(handle)) {
+        sink.set_volume(settings.music_volume());
+    }
+}
+
+// endregion: --- Music
+
+fn mute_toggle_system(mut kb: ResMut<Input<KeyCode>>, mut settings: ResMut<Settings>, state: Res<State<GameState>>) {
+    // not while typing initials or rebinding keys
+    if matches!(state.current(), GameState::NameEntry | GameState::Options) {
+        return;
+    }
+    if kb.just_pressed(MUTE_KEY) {
+        kb.reset(MUTE_KEY);
+        settings.muted = !settings.muted;
+        settings.save();
+    }
+}
@@ -0,0 +1,179 @@
+use bevy::{prelude::*, app::AppExit};
+
+use crate::{GameFonts, score::Score, highscore::HighScores, controls::Action, settings::Settings};
+
+// region: --- Game State
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameState {
+    Title,
+    Options,
+    Playing,
+    Paused,
+    NameEntry,
+    GameOver,
+}
+
+// endregion: --- Game State
+
+// region: --- Screen Components
+
+#[derive(Component)]
+struct TitleScreen;
+
+#[derive(Component)]
+struct PausedScreen;
+
+#[derive(Component)]
+struct GameOverScreen;
+
+// endregion: --- Screen Components
+
+pub struct StatePlugin;
+
+impl Plugin for StatePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_state(GameState::Title)
+            .add_system_set(SystemSet::on_enter(GameState::Title).with_system(title_screen_system))
+            .add_system_set(SystemSet::on_update(GameState::Title).with_system(title_input_system))
+            .add_system_set(SystemSet::on_exit(GameState::Title).with_system(despawn_with::<TitleScreen>))
+            .add_system_set(SystemSet::on_update(GameState::Playing).with_system(playing_input_system))
+            .add_system_set(SystemSet::on_enter(GameState::Paused).with_system(paused_screen_system))
+            .add_system_set(SystemSet::on_update(GameState::Paused).with_system(paused_input_system))
+            .add_system_set(SystemSet::on_exit(GameState::Paused).with_system(despawn_with::<PausedScreen>))
+            .add_system_set(SystemSet::on_enter(GameState::GameOver).with_system(game_over_screen_system))
+            .add_system_set(SystemSet::on_update(GameState::GameOver).with_system(game_over_input_system))
+            .add_system_set(SystemSet::on_exit(GameState::GameOver).with_system(despawn_with::<GameOverScreen>));
+    }
+}
+
+/// Despawn every entity (and its children) carrying the `T` marker.
+/// Used on state exit to clean up screens and gameplay entities.
+pub fn despawn_with<T: Component>(mut commands: Commands, query: Query<Entity, With<T>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// region: --- Screens
+
+pub fn spawn_screen(commands: &mut Commands, font: &Handle<Font>, lines: &[(&str, f32)]) -> Entity {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            for (text, font_size) in lines {
+                parent.spawn(TextBundle::from_section(
+                    *text,
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: *font_size,
+                        color: Color::WHITE,
+                    },
+                ));
+            }
+        })
+        .id()
+}
+
+fn title_screen_system(mut commands: Commands, fonts: Res<GameFonts>) {
+    let lines = [("RUST INVADERS", 56.), ("Press Enter to start", 24.), ("O for options", 18.), ("Esc to quit", 18.)];
+    let screen = spawn_screen(&mut commands, &fonts.main, &lines);
+    commands.entity(screen).insert(TitleScreen);
+}
+
+fn paused_screen_system(mut commands: Commands, fonts: Res<GameFonts>, settings: Res<Settings>) {
+    let resume = format!("{} to resume", settings.bindings.describe(Action::Pause));
+    let lines = [("PAUSED", 56.), (resume.as_str(), 24.), ("Q to quit to title", 18.)];
+    let screen = spawn_screen(&mut commands, &fonts.main, &lines);
+    commands.entity(screen).insert(PausedScreen);
+}
+
+fn game_over_screen_system(
+    mut commands: Commands,
+    fonts: Res<GameFonts>,
+    score: Res<Score>,
+    high_scores: Res<HighScores>,
+) {
+    let score = format!("Score {}", score.value);
+    let table: Vec<String> = high_scores
+        .0
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| format!("{:>2}. {}  {:>7}  {}", i + 1, entry.initials, entry.score, entry.date))
+        .collect();
+
+    let mut lines = vec![("GAME OVER", 56.), (score.as_str(), 32.), ("HIGH SCORES", 24.)];
+    lines.extend(table.iter().map(|line| (line.as_str(), 18.)));
+    lines.extend([("Press Enter to play again", 24.), ("Esc for title", 18.)]);
+    let screen = spawn_screen(&mut commands, &fonts.main, &lines);
+    commands.entity(screen).insert(GameOverScreen);
+}
+
+// endregion: --- Screens
+
+// region: --- Transitions
+
+// Note: keys are reset once handled, so the same press does not also trigger
+//       the next state's input system within the same frame.
+
+fn title_input_system(
+    mut kb: ResMut<Input<KeyCode>>,
+    mut state: ResMut<State<GameState>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if kb.just_pressed(KeyCode::Return) {
+        kb.reset(KeyCode::Return);
+        state.set(GameState::Playing).ok();
+    } else if kb.just_pressed(KeyCode::O) {
+        kb.reset(KeyCode::O);
+        state.set(GameState::Options).ok();
+    } else if kb.just_pressed(KeyCode::Escape) {
+        exit.send(AppExit);
+    }
+}
+
+fn playing_input_system(mut actions: ResMut<Input<Action>>, mut state: ResMut<State<GameState>>) {
+    if actions.just_pressed(Action::Pause) {
+        actions.reset(Action::Pause);
+        state.push(GameState::Paused).ok();
+    }
+}
+
+fn paused_input_system(
+    mut kb: ResMut<Input<KeyCode>>,
+    mut actions: ResMut<Input<Action>>,
+    mut state: ResMut<State<GameState>>,
+) {
+    if actions.just_pressed(Action::Pause) {
+        actions.reset(Action::Pause);
+        state.pop().ok();
+        return;
+    }
+    if kb.just_pressed(KeyCode::Q) {
+        kb.reset(KeyCode::Q);
+        // replace unwinds the stack, so Playing's on_exit cleanup runs too
+        state.replace(GameState::Title).ok();
+    }
+}
+
+fn game_over_input_system(mut kb: ResMut<Input<KeyCode>>, mut state: ResMut<State<GameState>>) {
+    if kb.just_pressed(KeyCode::Return) {
+        kb.reset(KeyCode::Return);
+        state.set(GameState::Playing).ok();
+    } else if kb.just_pressed(KeyCode::Escape) {
+        kb.reset(KeyCode::Escape);
+        state.set(GameState::Title).ok();
+    }
+}
+
+// endregion: --- Transitions
@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+
+use crate::{GameTextures, SPRITE_SCALE, PLAYER_SIZE, PLAYER_LASER_SIZE, PLAYER_LASER_DAMAGE, PLAYER_FIRE_COOLDOWN, ENEMY_LASER_SIZE, ENEMY_LASER_DAMAGE, ENEMY_FIRE_COOLDOWN, SPREAD_SHOT_SLOPE, components::{Weapon, Projectile, Muzzle, Laser, Damage, Movable, Velocity, SpriteSize, FromPlayer, FromEnemy}, collision::{Collider, Layer}};
+
+const PLAYER_MUZZLE_X: f32 = PLAYER_SIZE.0 / 2. * SPRITE_SCALE - 5.; // wings
+const PLAYER_MUZZLE_Y: f32 = 15.;
+const ENEMY_MUZZLE_Y: f32 = -15.;
+
+/// Spread shot muzzles, replacing the player blaster's: the nose, and the wings angled outward.
+pub(crate) const SPREAD_MUZZLES: [Muzzle; 3] = [
+    Muzzle::new(0., PLAYER_MUZZLE_Y),
+    Muzzle::angled(PLAYER_MUZZLE_X, PLAYER_MUZZLE_Y, SPREAD_SHOT_SLOPE),
+    Muzzle::angled(-PLAYER_MUZZLE_X, PLAYER_MUZZLE_Y, -SPREAD_SHOT_SLOPE),
+];
+
+// region: --- Weapon Definitions
+
+impl Weapon {
+    /// Twin wing lasers of the player ship, firing on its own while held.
+    pub(crate) fn player_blaster(game_textures: &GameTextures) -> Self {
+        Self {
+            projectile: Projectile {
+                texture: game_textures.player_laser.clone(),
+                size: SpriteSize::from(PLAYER_LASER_SIZE),
+                velocity: Velocity { x: 0., y: 1. },
+                damage: PLAYER_LASER_DAMAGE,
+                collider: Collider::new(Layer::PlayerLaser, &[Layer::Enemy, Layer::Bunker]),
+            },
+            muzzles: vec![Muzzle::new(PLAYER_MUZZLE_X, PLAYER_MUZZLE_Y), Muzzle::new(-PLAYER_MUZZLE_X, PLAYER_MUZZLE_Y)],
+            cooldown: PLAYER_FIRE_COOLDOWN,
+            auto_fire: true,
+            last_fire: None,
+        }
+    }
+
+    /// Single laser dropped from the belly of an enemy.
+    pub(crate) fn enemy_cannon(game_textures: &GameTextures) -> Self {
+        Self {
+            projectile: Projectile {
+                texture: game_textures.enemy_laser.clone(),
+                size: SpriteSize::from(ENEMY_LASER_SIZE),
+                velocity: Velocity { x: 0., y: -1. },
+                damage: ENEMY_LASER_DAMAGE,
+                collider: Collider::new(Layer::EnemyLaser, &[Layer::Player, Layer::Bunker]),
+            },
+            muzzles: vec![Muzzle::new(0., ENEMY_MUZZLE_Y)],
+            cooldown: ENEMY_FIRE_COOLDOWN,
+            auto_fire: false,
+            last_fire: None,
+        }
+    }
+}
+
+// endregion: --- Weapon Definitions
+
+impl Weapon {
+    /// Whether `cooldown` (the weapon's own, or one shortened by a power-up) passed since the last shot.
+    pub fn ready(&self, now: f64, cooldown: f64) -> bool {
+        self.last_fire.is_none_or(|last_fire| now - last_fire >= cooldown)
+    }
+
+    /// Spawn one projectile per muzzle from the ship at `origin`, `muzzles` overriding the weapon's own.
+    pub(crate) fn fire(&mut self, commands: &mut Commands, origin: &Transform, muzzles: Option<&[Muzzle]>, now: f64) {
+        self.last_fire = Some(now);
+        let projectile = &self.projectile;
+
+        for muzzle in muzzles.unwrap_or(&self.muzzles) {
+            let velocity = Velocity {
+                x: projectile.velocity.x + muzzle.slope,
+                y: projectile.velocity.y,
+            };
+            let translation = origin.translation.truncate() + muzzle.offset;
+            let mut laser = commands.spawn(SpriteBundle {
+                texture: projectile.texture.clone(),
+                transform: Transform {
+                    translation: translation.extend(0.),
+                    // sprites point up, turn them to face their velocity
+                    rotation: Quat::from_rotation_z(-velocity.x.atan2(velocity.y)),
+                    scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.),
+                },
+                ..default()
+            });
+            laser
+                .insert(Laser)
+                .insert(Damage(projectile.damage))
+                .insert(projectile.collider)
+                .insert(projectile.size)
+                .insert(velocity)
+                .insert(Movable { auto_despawn: true });
+
+            // owner, from the layer the projectile flies on
+            if projectile.collider.layer == Layer::PlayerLaser {
+                laser.insert(FromPlayer);
+            } else {
+                laser.insert(FromEnemy);
+            }
+        }
+    }
+}
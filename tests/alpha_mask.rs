@@ -0,0 +1,81 @@
+use bevy::{prelude::*, render::render_resource::{Extent3d, TextureDimension, TextureFormat}};
+use my_space_invaders::collision::{masks_overlap, AlphaMask, PlacedMask};
+
+const SIZE: u32 = 8;
+
+/// 8x8 sprite, opaque in its 4x4 center only - the corners are transparent.
+fn sprite_image(format: TextureFormat) -> Image {
+    let mut data = Vec::new();
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let opaque = (2..6).contains(&x) && (2..6).contains(&y);
+            data.extend([255, 255, 255, if opaque { 255 } else { 0 }]);
+        }
+    }
+    let size = Extent3d { width: SIZE, height: SIZE, depth_or_array_layers: 1 };
+    Image::new(size, TextureDimension::D2, data, format)
+}
+
+fn mask() -> AlphaMask {
+    AlphaMask::from_image(&sprite_image(TextureFormat::Rgba8UnormSrgb)).unwrap()
+}
+
+/// Pixel perfect test of two such sprites, over their bounding box overlap
+/// (as `collision_system` does).
+fn overlap(tf: Transform, other_tf: Transform) -> bool {
+    let mask = mask();
+    let size = Vec2::splat(SIZE as f32);
+    let (half, other_half) = (size * tf.scale.truncate() / 2., size * other_tf.scale.truncate() / 2.);
+    let (center, other_center) = (tf.translation.truncate(), other_tf.translation.truncate());
+    let min = (center - half).max(other_center - other_half);
+    let max = (center + half).min(other_center + other_half);
+    assert!(min.cmplt(max).all(), "bounding boxes don't overlap");
+
+    let step = tf.scale.truncate().min(other_tf.scale.truncate()).min_element();
+    masks_overlap(&[PlacedMask::new(&mask, &tf), PlacedMask::new(&mask, &other_tf)], min, max, step)
+}
+
+#[test]
+fn mask_follows_the_alpha_channel() {
+    let mask = mask();
+    assert!(mask.is_opaque(2, 2));
+    assert!(mask.is_opaque(5, 5));
+    assert!(!mask.is_opaque(0, 0));
+    assert!(!mask.is_opaque(7, 1));
+    // outside of the image
+    assert!(!mask.is_opaque(-1, 3));
+    assert!(!mask.is_opaque(3, 8));
+
+    assert!(AlphaMask::from_image(&sprite_image(TextureFormat::Rgba8Unorm)).is_some());
+    assert!(AlphaMask::from_image(&sprite_image(TextureFormat::Bgra8UnormSrgb)).is_none());
+}
+
+#[test]
+fn transparent_corners_dont_collide() {
+    // bounding boxes overlap by their 3x3 corners
+    assert!(!overlap(Transform::default(), Transform::from_xyz(5., 5., 0.)));
+}
+
+#[test]
+fn opaque_texels_collide() {
+    assert!(overlap(Transform::default(), Transform::from_xyz(3., 3., 0.)));
+    assert!(overlap(Transform::default(), Transform::from_xyz(-3., 0., 0.)));
+}
+
+#[test]
+fn offset_moves_the_opaque_texels() {
+    // opaque centers are 4 wide, the bounding boxes 8
+    assert!(overlap(Transform::default(), Transform::from_xyz(3., 0., 0.)));
+    assert!(!overlap(Transform::default(), Transform::from_xyz(5., 0., 0.)));
+    assert!(overlap(Transform::from_xyz(10., 10., 0.), Transform::from_xyz(13., 10., 0.)));
+    assert!(!overlap(Transform::from_xyz(10., 10., 0.), Transform::from_xyz(15., 10., 0.)));
+}
+
+#[test]
+fn scale_grows_the_opaque_texels() {
+    let other = Transform::from_xyz(5., 5., 0.);
+    assert!(!overlap(Transform::default(), other));
+    // twice the size, the opaque center reaches 4 units out
+    assert!(overlap(Transform::default(), other.with_scale(Vec3::new(2., 2., 1.))));
+    assert!(overlap(Transform::default().with_scale(Vec3::new(2., 2., 1.)), other));
+}
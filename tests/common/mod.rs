@@ -0,0 +1,38 @@
+#![allow(dead_code)] // each test crate uses its own subset
+
+use bevy::prelude::*;
+use my_space_invaders::{WinSize, headless::{headless_app, step}, sound::SoundEvent, state::GameState};
+
+pub const SEED: u64 = 42;
+pub const SECOND: u32 = 60; // ticks
+
+pub fn app() -> App {
+    app_with_seed(SEED)
+}
+
+pub fn app_with_seed(seed: u64) -> App {
+    headless_app(WinSize { w: 598., h: 676. }, seed)
+}
+
+/// Headless app, with a game started and the player spawned.
+pub fn playing_app() -> App {
+    let mut app = app();
+    step(&mut app, 1);
+    app.world.resource_mut::<State<GameState>>().set(GameState::Playing).unwrap();
+    step(&mut app, 1);
+    app
+}
+
+pub fn entities<T: Component>(app: &mut App) -> Vec<Entity> {
+    app.world.query_filtered::<Entity, With<T>>().iter(&app.world).collect()
+}
+
+pub fn state(app: &App) -> GameState {
+    *app.world.resource::<State<GameState>>().current()
+}
+
+/// Sound effects requested over the last two updates.
+pub fn sounds(app: &App) -> Vec<SoundEvent> {
+    let events = app.world.resource::<Events<SoundEvent>>();
+    events.get_reader().iter(events).copied().collect()
+}
@@ -0,0 +1,210 @@
+use bevy::{prelude::*, input::gamepad::GamepadInfo};
+use my_space_invaders::{
+    components::{FromPlayer, Laser, Player},
+    controls::Action,
+    headless::{send_gamepad_event, step, InputScript},
+    settings::Settings,
+    sound::SoundEvent,
+    state::GameState,
+};
+
+use common::{entities, playing_app, sounds, state};
+
+mod common;
+
+// player moves at BASE_SPEED (300) per second, the headless tick is 1/60 second
+const TICK_DISTANCE: f32 = 5.;
+// laser spawn offsets from player_fire_system, for the 0.5 sprite scale
+const LASER_X_OFFSET: f32 = 144. / 2. * 0.5 - 5.;
+const LASER_Y_OFFSET: f32 = 15.;
+
+fn translation(app: &App, entity: Entity) -> Vec3 {
+    app.world.get::<Transform>(entity).unwrap().translation
+}
+
+fn player_translation(app: &mut App) -> Vec3 {
+    let player = entities::<Player>(app)[0];
+    translation(app, player)
+}
+
+fn player_lasers(app: &mut App) -> Vec<Vec3> {
+    let mut lasers: Vec<Vec3> = app
+        .world
+        .query_filtered::<&Transform, (With<Laser>, With<FromPlayer>)>()
+        .iter(&app.world)
+        .map(|tf| tf.translation)
+        .collect();
+    lasers.sort_by(|a, b| a.x.total_cmp(&b.x));
+    lasers
+}
+
+fn connect_gamepad(app: &mut App) -> Gamepad {
+    let gamepad = Gamepad::new(0);
+    send_gamepad_event(app, gamepad, GamepadEventType::Connected(GamepadInfo { name: "test pad".to_string() }));
+    step(app, 1);
+    gamepad
+}
+
+fn assert_near(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 0.01, "expected {expected}, got {actual}");
+}
+
+#[test]
+fn holding_right_moves_right() {
+    let mut app = playing_app();
+    let start = player_translation(&mut app);
+
+    InputScript::new().hold(KeyCode::Right, 30).run(&mut app);
+
+    let end = player_translation(&mut app);
+    assert_near(end.x - start.x, 30. * TICK_DISTANCE);
+    assert_eq!(end.y, start.y);
+}
+
+#[test]
+fn player_stops_when_key_released() {
+    let mut app = playing_app();
+    let start = player_translation(&mut app);
+
+    InputScript::new().hold(KeyCode::Left, 10).wait(20).run(&mut app);
+
+    assert_near(player_translation(&mut app).x - start.x, -10. * TICK_DISTANCE);
+}
+
+#[test]
+fn space_fires_two_lasers_from_the_wings() {
+    let mut app = playing_app();
+
+    InputScript::new().hold(KeyCode::Right, 30).press(KeyCode::Space).run(&mut app);
+
+    let player = player_translation(&mut app);
+    let lasers = player_lasers(&mut app);
+    assert_eq!(lasers.len(), 2);
+    assert_near(lasers[0].x, player.x - LASER_X_OFFSET);
+    assert_near(lasers[1].x, player.x + LASER_X_OFFSET);
+    for laser in lasers {
+        assert_near(laser.y, player.y + LASER_Y_OFFSET);
+    }
+}
+
+#[test]
+fn firing_plays_the_laser_sound() {
+    let mut app = playing_app();
+
+    InputScript::new().press(KeyCode::Space).run(&mut app);
+
+    assert_eq!(sounds(&app), vec![SoundEvent::PlayerFire]);
+}
+
+#[test]
+fn holding_space_autofires_at_the_weapon_rate() {
+    let mut app = playing_app();
+
+    // a volley every 0.25s
+    InputScript::new().hold(KeyCode::Space, 10).run(&mut app);
+    assert_eq!(player_lasers(&mut app).len(), 2);
+
+    InputScript::new().hold(KeyCode::Space, 40).run(&mut app);
+    assert_eq!(player_lasers(&mut app).len(), 2 + 3 * 2);
+}
+
+#[test]
+fn tapping_faster_than_the_cooldown_is_capped() {
+    let mut app = playing_app();
+
+    // 10 taps within half a second
+    let mut script = InputScript::new();
+    for _ in 0..10 {
+        script = script.press(KeyCode::Space).wait(2);
+    }
+    script.run(&mut app);
+
+    assert_eq!(player_lasers(&mut app).len(), 2 * 2);
+}
+
+#[test]
+fn escape_pauses_and_p_resumes() {
+    let mut app = playing_app();
+
+    InputScript::new().press(KeyCode::Escape).wait(1).run(&mut app);
+    assert_eq!(state(&app), GameState::Paused);
+
+    // nothing moves while paused
+    let start = player_translation(&mut app);
+    InputScript::new().hold(KeyCode::Right, 10).run(&mut app);
+    assert_eq!(player_translation(&mut app), start);
+
+    InputScript::new().press(KeyCode::P).wait(1).run(&mut app);
+    assert_eq!(state(&app), GameState::Playing);
+}
+
+#[test]
+fn wasd_alternates_move_and_fire() {
+    let mut app = playing_app();
+    let start = player_translation(&mut app);
+
+    InputScript::new().hold(KeyCode::D, 10).press(KeyCode::W).run(&mut app);
+
+    assert_near(player_translation(&mut app).x - start.x, 10. * TICK_DISTANCE);
+    assert_eq!(player_lasers(&mut app).len(), 2);
+}
+
+#[test]
+fn rebound_key_fires_instead_of_space() {
+    let mut app = playing_app();
+    app.world.resource_mut::<Settings>().bindings.rebind(Action::Fire, KeyCode::F);
+
+    InputScript::new().press(KeyCode::Space).wait(1).run(&mut app);
+    assert!(player_lasers(&mut app).is_empty());
+
+    InputScript::new().press(KeyCode::F).run(&mut app);
+    assert_eq!(player_lasers(&mut app).len(), 2);
+}
+
+#[test]
+fn left_stick_moves_proportionally() {
+    let mut app = playing_app();
+    let gamepad = connect_gamepad(&mut app);
+    let start = player_translation(&mut app);
+
+    // half way between the 0.2 deadzone and full tilt - half speed
+    send_gamepad_event(&mut app, gamepad, GamepadEventType::AxisChanged(GamepadAxisType::LeftStickX, 0.6));
+    step(&mut app, 10);
+
+    assert_near(player_translation(&mut app).x - start.x, 10. * TICK_DISTANCE / 2.);
+}
+
+#[test]
+fn left_stick_deadzone_ignores_drift() {
+    let mut app = playing_app();
+    let gamepad = connect_gamepad(&mut app);
+    let start = player_translation(&mut app);
+
+    send_gamepad_event(&mut app, gamepad, GamepadEventType::AxisChanged(GamepadAxisType::LeftStickX, -0.15));
+    step(&mut app, 10);
+
+    assert_eq!(player_translation(&mut app), start);
+}
+
+#[test]
+fn gamepad_south_button_fires() {
+    let mut app = playing_app();
+    let gamepad = connect_gamepad(&mut app);
+
+    send_gamepad_event(&mut app, gamepad, GamepadEventType::ButtonChanged(GamepadButtonType::South, 1.));
+    step(&mut app, 10);
+
+    assert_eq!(player_lasers(&mut app).len(), 2);
+}
+
+#[test]
+fn unplugging_the_gamepad_pauses() {
+    let mut app = playing_app();
+    let gamepad = connect_gamepad(&mut app);
+    assert_eq!(state(&app), GameState::Playing);
+
+    send_gamepad_event(&mut app, gamepad, GamepadEventType::Disconnected);
+    step(&mut app, 1);
+
+    assert_eq!(state(&app), GameState::Paused);
+}
@@ -0,0 +1,278 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, window::{WindowId, WindowResized}};
+use my_space_invaders::{
+    EnemyCount, PlayerState, WinSize,
+    bunker::BUNKER_SHAPE,
+    components::{BunkerCell, Enemy, EnemyKind, Explosion, ExplosionToSpawn, FromEnemy, FromPlayer, Health, Invulnerable, Laser, Player},
+    damage::DamageEvent,
+    headless::{press_key, release_key, step, step_by, wait_for_waves, InputScript},
+    score::Score,
+    sound::SoundEvent,
+    state::GameState,
+};
+
+use common::{app, app_with_seed, entities, playing_app, sounds, state, SECOND, SEED};
+
+mod common;
+
+fn kill(app: &mut App, target: Entity) {
+    app.world.resource_mut::<Events<DamageEvent>>().send(DamageEvent { target, amount: u32::MAX });
+    step(app, 1);
+}
+
+#[test]
+fn starts_on_title_screen() {
+    let mut app = app();
+    step(&mut app, 10);
+
+    assert_eq!(state(&app), GameState::Title);
+    assert!(entities::<Player>(&mut app).is_empty());
+}
+
+#[test]
+fn resizing_the_window_keeps_the_playfield() {
+    let mut app = app();
+    step(&mut app, 1);
+    app.world.resource_mut::<Events<WindowResized>>().send(WindowResized {
+        id: WindowId::primary(),
+        width: 1280.,
+        height: 720.,
+    });
+    step(&mut app, 1);
+
+    let win_size = app.world.resource::<WinSize>();
+    assert_eq!((win_size.w, win_size.h), (1280., 720.));
+
+    // the player still spawns at the bottom of the 598x676 playfield
+    app.world.resource_mut::<State<GameState>>().set(GameState::Playing).unwrap();
+    step(&mut app, 1);
+    let player = entities::<Player>(&mut app)[0];
+    assert_eq!(app.world.get::<Transform>(player).unwrap().translation.y, -676. / 2. + 75. / 2. + 5.);
+}
+
+#[test]
+fn player_spawns_when_playing() {
+    let mut app = playing_app();
+
+    assert_eq!(entities::<Player>(&mut app).len(), 1);
+    let player_state = app.world.resource::<PlayerState>();
+    assert!(player_state.on);
+    assert_eq!(player_state.lives, 3);
+    assert_eq!(app.world.resource::<Score>().value, 0);
+}
+
+#[test]
+fn first_wave_spawns_its_enemies() {
+    let mut app = playing_app();
+    // banner, then one enemy every 2 seconds
+    step(&mut app, 3 * SECOND);
+    assert_eq!(app.world.resource::<EnemyCount>().0, 0);
+
+    step(&mut app, 8 * SECOND);
+    assert_eq!(app.world.resource::<EnemyCount>().0, 4);
+    assert_eq!(entities::<Enemy>(&mut app).len(), 4);
+}
+
+#[test]
+fn killing_an_enemy_scores() {
+    let mut app = playing_app();
+    step(&mut app, 5 * SECOND);
+    let enemies = entities::<Enemy>(&mut app);
+    assert_eq!(enemies.len(), 1);
+
+    kill(&mut app, enemies[0]);
+
+    assert!(entities::<Enemy>(&mut app).is_empty());
+    assert_eq!(app.world.resource::<EnemyCount>().0, 0);
+    assert_eq!(app.world.resource::<Score>().value, 100);
+
+    step(&mut app, 1);
+    assert!(sounds(&app).contains(&SoundEvent::Explosion));
+}
+
+#[test]
+fn pausing_keeps_the_combo_going() {
+    let mut app = playing_app();
+    step(&mut app, 5 * SECOND);
+    let enemies = entities::<Enemy>(&mut app);
+    kill(&mut app, enemies[0]);
+    assert_eq!(app.world.resource::<Score>().combo, 1);
+
+    // paused well past the combo window
+    InputScript::new().press(KeyCode::Escape).wait(3 * SECOND).press(KeyCode::Escape).wait(1).run(&mut app);
+    assert_eq!(state(&app), GameState::Playing);
+    assert_eq!(app.world.resource::<Score>().combo, 1);
+}
+
+fn enemy_lasers(app: &mut App) -> usize {
+    app.world.query_filtered::<(), (With<Laser>, With<FromEnemy>)>().iter(&app.world).count()
+}
+
+#[test]
+fn enemy_lasers_on_screen_are_capped() {
+    let mut app = playing_app();
+
+    let mut most = 0;
+    for _ in 0..30 * SECOND {
+        step(&mut app, 1);
+        most = most.max(enemy_lasers(&mut app));
+    }
+    assert!(most > 0);
+    assert!(most <= 6);
+}
+
+#[test]
+fn enemies_only_fire_at_the_player() {
+    let mut app = playing_app();
+    // the ship gone, without dying - nothing to aim at
+    let player = entities::<Player>(&mut app)[0];
+    app.world.despawn(player);
+
+    step(&mut app, 10 * SECOND);
+    assert!(!entities::<Enemy>(&mut app).is_empty());
+    assert_eq!(enemy_lasers(&mut app), 0);
+}
+
+#[test]
+fn player_respawns_invulnerable_after_death() {
+    let mut app = playing_app();
+    let player = entities::<Player>(&mut app)[0];
+
+    kill(&mut app, player);
+    assert!(entities::<Player>(&mut app).is_empty());
+    assert!(!app.world.resource::<PlayerState>().on);
+    assert_eq!(app.world.resource::<PlayerState>().lives, 2);
+
+    step(&mut app, 3 * SECOND);
+    assert_eq!(entities::<Player>(&mut app).len(), 1);
+    assert_eq!(entities::<Invulnerable>(&mut app).len(), 1);
+}
+
+#[test]
+fn game_over_when_out_of_lives() {
+    let mut app = playing_app();
+    while app.world.resource::<PlayerState>().lives > 0 {
+        match entities::<Player>(&mut app).first() {
+            Some(&player) => kill(&mut app, player),
+            None => step(&mut app, 1),
+        }
+    }
+    assert_eq!(state(&app), GameState::Playing);
+
+    step(&mut app, 3 * SECOND);
+    assert_eq!(state(&app), GameState::GameOver);
+    assert!(entities::<Player>(&mut app).is_empty());
+}
+
+#[test]
+fn bunkers_stand_between_player_and_enemies() {
+    let mut app = playing_app();
+    step(&mut app, 1);
+
+    // 4 bunkers, above the player
+    let cells = entities::<BunkerCell>(&mut app);
+    let bunker_cells: usize = BUNKER_SHAPE.iter().map(|line| line.matches('#').count()).sum();
+    assert_eq!(cells.len(), 4 * bunker_cells);
+    let player = entities::<Player>(&mut app)[0];
+    let player_y = app.world.get::<Transform>(player).unwrap().translation.y;
+    for cell in cells {
+        assert!(app.world.get::<Transform>(cell).unwrap().translation.y > player_y + 50.);
+    }
+}
+
+fn bunker_health(app: &mut App) -> u32 {
+    app.world.query_filtered::<&Health, With<BunkerCell>>().iter(&app.world).map(|health| health.current).sum()
+}
+
+#[test]
+fn lasers_erode_bunker_cells() {
+    let mut app = playing_app();
+    step(&mut app, 1);
+    let full_health = bunker_health(&mut app);
+
+    // right wing under the second bunker from the right (x = 75), left wing in the clear
+    InputScript::new().hold(KeyCode::Right, 9).press(KeyCode::Space).wait(30).run(&mut app);
+    assert_eq!(bunker_health(&mut app), full_health - 1);
+
+    InputScript::new().press(KeyCode::Space).wait(30).run(&mut app);
+    assert_eq!(bunker_health(&mut app), full_health - 2);
+    // the bunker stopped the right wing lasers only
+    let lasers = app.world.query_filtered::<(), (With<Laser>, With<FromPlayer>)>().iter(&app.world).count();
+    assert_eq!(lasers, 2);
+}
+
+#[test]
+fn destroyed_bunker_cells_are_removed() {
+    let mut app = playing_app();
+    step(&mut app, 1);
+    let cells = entities::<BunkerCell>(&mut app);
+
+    kill(&mut app, cells[0]);
+
+    assert_eq!(entities::<BunkerCell>(&mut app).len(), cells.len() - 1);
+}
+
+/// Moves right for half a second, fires for a second, then lets an explosion play out,
+/// all at `fps`. Returns the distance moved, the lasers fired and the explosion lifetime.
+fn play_at(fps: u32) -> (f32, usize, f32) {
+    let tick = Duration::from_secs_f64(1. / fps as f64);
+    let ticks = |seconds: f32| (seconds * fps as f32).round() as u32;
+    let mut app = playing_app();
+    let player = entities::<Player>(&mut app)[0];
+    let start = app.world.get::<Transform>(player).unwrap().translation.x;
+
+    press_key(&mut app, KeyCode::Right);
+    step_by(&mut app, ticks(0.5), tick);
+    release_key(&mut app, KeyCode::Right);
+    press_key(&mut app, KeyCode::Space);
+    step_by(&mut app, ticks(1.), tick);
+    release_key(&mut app, KeyCode::Space);
+    step_by(&mut app, 1, tick);
+    let distance = app.world.get::<Transform>(player).unwrap().translation.x - start;
+    let lasers = app.world.query_filtered::<(), (With<Laser>, With<FromPlayer>)>().iter(&app.world).count();
+
+    app.world.spawn(ExplosionToSpawn(Vec3::ZERO));
+    step_by(&mut app, 1, tick);
+    let mut lifetime = 0.;
+    while !entities::<Explosion>(&mut app).is_empty() {
+        step_by(&mut app, 1, tick);
+        lifetime += tick.as_secs_f32();
+    }
+    (distance, lasers, lifetime)
+}
+
+#[test]
+fn same_game_at_30_60_and_144_fps() {
+    let (distance, lasers, lifetime) = play_at(60);
+    assert!((distance - 150.).abs() < 0.01);
+    assert_eq!(lasers, 4 * 2);
+
+    for fps in [30, 144] {
+        let (other_distance, other_lasers, other_lifetime) = play_at(fps);
+        assert!((other_distance - distance).abs() < 0.01, "{fps} fps moved {other_distance}");
+        assert_eq!(other_lasers, lasers, "{fps} fps");
+        // to the frame
+        assert!((other_lifetime - lifetime).abs() <= 1. / 30. + 0.001, "{fps} fps explosion lasted {other_lifetime}");
+    }
+}
+
+/// Kind and position of every enemy, after playing `seconds` of a game seeded with `seed`.
+fn enemies_after(seed: u64, seconds: u32) -> Vec<(EnemyKind, Vec3)> {
+    let mut app = app_with_seed(seed);
+    wait_for_waves(&mut app);
+    app.world.resource_mut::<State<GameState>>().set(GameState::Playing).unwrap();
+    step(&mut app, seconds * SECOND);
+    app.world.query_filtered::<(&EnemyKind, &Transform), With<Enemy>>()
+        .iter(&app.world)
+        .map(|(kind, tf)| (*kind, tf.translation))
+        .collect()
+}
+
+#[test]
+fn same_seed_spawns_the_same_enemies() {
+    let enemies = enemies_after(SEED, 8);
+    assert!(!enemies.is_empty());
+    assert_eq!(enemies_after(SEED, 8), enemies);
+    assert_ne!(enemies_after(SEED + 1, 8), enemies);
+}
@@ -0,0 +1,84 @@
+use std::{fs, path::PathBuf};
+
+use my_space_invaders::highscore::{utc_date, HighScoreEntry, HighScores};
+
+/// Scratch file in the temp directory, unique per test.
+fn temp_file(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("{}-{}", env!("CARGO_PKG_NAME"), std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir.join(name)
+}
+
+fn entry(initials: &str, score: u32) -> HighScoreEntry {
+    HighScoreEntry {
+        initials: initials.to_string(),
+        score,
+        date: "2024-01-01".to_string(),
+    }
+}
+
+fn scores(high_scores: &HighScores) -> Vec<u32> {
+    high_scores.0.iter().map(|entry| entry.score).collect()
+}
+
+#[test]
+fn missing_file_loads_the_defaults() {
+    let high_scores = HighScores::load_from(&temp_file("missing.ron"));
+    assert_eq!(scores(&high_scores), scores(&HighScores::default()));
+}
+
+#[test]
+fn corrupt_file_loads_the_defaults() {
+    let path = temp_file("corrupt.ron");
+    fs::write(&path, "([(initials: \"AAA\", score: ").unwrap();
+    let high_scores = HighScores::load_from(&path);
+    assert_eq!(scores(&high_scores), scores(&HighScores::default()));
+}
+
+#[test]
+fn saved_file_loads_sorted_and_capped() {
+    let path = temp_file("unsorted.ron");
+    let table = HighScores((1..=12).map(|i| entry("AAA", i * 100)).collect());
+    fs::write(&path, ron::to_string(&table).unwrap()).unwrap();
+
+    let high_scores = HighScores::load_from(&path);
+    assert_eq!(scores(&high_scores), (3..=12).rev().map(|i| i * 100).collect::<Vec<_>>());
+}
+
+#[test]
+fn qualifies_only_above_the_lowest_of_a_full_table() {
+    let high_scores = HighScores::default(); // 5000 down to 500
+    assert!(high_scores.qualifies(501));
+    assert!(!high_scores.qualifies(500)); // a tie doesn't push out the older entry
+    assert!(!high_scores.qualifies(100));
+
+    let short = HighScores(vec![entry("AAA", 1000)]);
+    assert!(short.qualifies(1));
+    assert!(!short.qualifies(0));
+}
+
+#[test]
+fn insert_ranks_the_entry_and_keeps_ten() {
+    let mut high_scores = HighScores::default();
+    high_scores.insert(entry("NEW", 2750));
+    assert_eq!(high_scores.0.len(), 10);
+    assert_eq!(high_scores.0[5].initials, "NEW");
+    assert_eq!(high_scores.0.last().unwrap().score, 1000); // 500 dropped
+
+    // ties rank below the older entry
+    high_scores.insert(entry("TIE", 2750));
+    assert_eq!(high_scores.0[5].initials, "NEW");
+    assert_eq!(high_scores.0[6].initials, "TIE");
+    assert_eq!(high_scores.0.len(), 10);
+}
+
+#[test]
+fn utc_date_converts_unix_timestamps() {
+    assert_eq!(utc_date(0), "1970-01-01");
+    assert_eq!(utc_date(86_399), "1970-01-01");
+    assert_eq!(utc_date(951_782_400), "2000-02-29"); // leap day of a 400 year
+    assert_eq!(utc_date(1_709_251_199), "2024-02-29");
+    assert_eq!(utc_date(1_709_251_200), "2024-03-01");
+    assert_eq!(utc_date(1_735_689_599), "2024-12-31");
+    assert_eq!(utc_date(4_107_542_400), "2100-03-01"); // 2100 is no leap year
+}
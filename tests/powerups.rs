@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use my_space_invaders::{
+    PlayerState,
+    components::{FromPlayer, Laser, Player, PowerUp, PowerUpKind, RapidFire, Shield, SpreadShot},
+    damage::DamageEvent,
+    headless::{step, InputScript},
+    powerup::power_up_bundle,
+};
+
+use common::{entities, playing_app, SECOND};
+
+mod common;
+
+fn player_lasers(app: &mut App) -> usize {
+    app.world.query_filtered::<(), (With<Laser>, With<FromPlayer>)>().iter(&app.world).count()
+}
+
+/// Drop a power-up right on the player, and let it be collected.
+fn collect(app: &mut App, kind: PowerUpKind) -> Entity {
+    let player = entities::<Player>(app)[0];
+    let translation = app.world.get::<Transform>(player).unwrap().translation;
+    app.world.spawn(power_up_bundle(kind, translation));
+    step(app, 2);
+    player
+}
+
+#[test]
+fn collected_spread_shot_fires_three_lasers() {
+    let mut app = playing_app();
+    let player = collect(&mut app, PowerUpKind::SpreadShot);
+
+    assert!(entities::<PowerUp>(&mut app).is_empty());
+    assert!(app.world.get::<SpreadShot>(player).is_some());
+
+    InputScript::new().press(KeyCode::Space).run(&mut app);
+    assert_eq!(player_lasers(&mut app), 3);
+}
+
+#[test]
+fn rapid_fire_keeps_firing_while_held() {
+    let mut app = playing_app();
+    let player = entities::<Player>(&mut app)[0];
+    app.world.entity_mut(player).insert(RapidFire::default());
+
+    // a volley every 0.12s, over half a second
+    InputScript::new().hold(KeyCode::Space, SECOND / 2).run(&mut app);
+    assert_eq!(player_lasers(&mut app), 4 * 2);
+}
+
+#[test]
+fn shield_absorbs_one_hit() {
+    let mut app = playing_app();
+    let player = collect(&mut app, PowerUpKind::Shield);
+    assert!(app.world.get::<Shield>(player).is_some());
+
+    app.world.resource_mut::<Events<DamageEvent>>().send(DamageEvent { target: player, amount: 1 });
+    step(&mut app, 1);
+    assert_eq!(entities::<Player>(&mut app), vec![player]);
+    assert!(app.world.get::<Shield>(player).is_none());
+
+    app.world.resource_mut::<Events<DamageEvent>>().send(DamageEvent { target: player, amount: 1 });
+    step(&mut app, 1);
+    assert!(entities::<Player>(&mut app).is_empty());
+}
+
+#[test]
+fn extra_life_adds_a_ship() {
+    let mut app = playing_app();
+    collect(&mut app, PowerUpKind::ExtraLife);
+
+    assert_eq!(app.world.resource::<PlayerState>().lives, 4);
+}
+
+#[test]
+fn power_ups_run_out() {
+    let mut app = playing_app();
+    let player = collect(&mut app, PowerUpKind::SpreadShot);
+
+    step(&mut app, 11 * SECOND);
+    assert!(app.world.get::<SpreadShot>(player).is_none());
+}
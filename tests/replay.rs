@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+use my_space_invaders::{
+    headless::{step, wait_for_waves, InputScript},
+    replay::{Playback, Recorder, ReplayPlugin},
+    score::Score,
+    state::GameState,
+};
+
+use common::{app, state, SECOND};
+
+mod common;
+
+/// Start a game, fire while moving around, then quit from the pause menu.
+fn play(app: &mut App) {
+    app.world.resource_mut::<State<GameState>>().set(GameState::Playing).unwrap();
+    step(app, 1);
+    InputScript::new()
+        .hold(KeyCode::Space, 3 * SECOND)
+        .hold(KeyCode::Left, SECOND / 2)
+        .hold(KeyCode::Space, 3 * SECOND)
+        .hold(KeyCode::Right, SECOND)
+        .hold(KeyCode::Space, 3 * SECOND)
+        .press(KeyCode::Escape)
+        .press(KeyCode::Q)
+        .wait(1)
+        .run(app);
+    assert_eq!(state(app), GameState::Title);
+}
+
+#[test]
+fn recorded_game_plays_back_to_the_same_score() {
+    let mut app = app();
+    app.add_plugin(ReplayPlugin::recording());
+    wait_for_waves(&mut app);
+    // the second game, which must not depend on the first one
+    play(&mut app);
+    play(&mut app);
+
+    let replay = app.world.resource::<Recorder>().last().expect("game recorded").clone();
+    let score = app.world.resource::<Score>().value;
+    assert_eq!(replay.score(), score);
+    assert!(score > 0, "nothing shot down, the playback would prove little");
+
+    // another seed, the replay's wins
+    let mut playback = common::app_with_seed(7);
+    playback.add_plugin(ReplayPlugin::playback(replay));
+    // driven by the recorded frame durations, not `step`
+    for _ in 0..100_000 {
+        if playback.world.resource::<Playback>().is_done() {
+            break;
+        }
+        playback.update();
+    }
+    assert!(playback.world.resource::<Playback>().is_done());
+    assert_eq!(playback.world.resource::<Score>().value, score);
+}
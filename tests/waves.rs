@@ -0,0 +1,83 @@
+use std::fs;
+
+use bevy::{prelude::*, asset::HandleId};
+use my_space_invaders::{components::EnemyKind, enemy::wave::{WaveConfig, WaveDefinitions}};
+
+use common::app;
+
+mod common;
+
+const WAVE: &str = "(enemies: 6, spawn_interval: 1.5, formations: 2, speed_multiplier: 1.2)";
+
+/// The fields a wave is scaled on.
+fn curve(config: &WaveConfig) -> (u32, f32, u32, f32) {
+    (config.enemies, config.spawn_interval, config.formations, config.speed_multiplier)
+}
+
+fn wave(enemies: u32) -> WaveConfig {
+    WaveConfig { enemies, ..default() }
+}
+
+#[test]
+fn authored_wave_files_load() {
+    let mut paths: Vec<_> = fs::read_dir("assets/waves").unwrap().map(|entry| entry.unwrap().path()).collect();
+    paths.sort();
+    assert!(!paths.is_empty());
+    for path in paths {
+        let bytes = fs::read(&path).unwrap();
+        assert!(WaveConfig::from_ron(&bytes).is_ok(), "{path:?}");
+    }
+}
+
+#[test]
+fn omitted_fields_take_their_defaults() {
+    let config = WaveConfig::from_ron(WAVE.as_bytes()).unwrap();
+    assert_eq!(curve(&config), (6, 1.5, 2, 1.2));
+    assert_eq!(config.kinds, vec![(EnemyKind::Grunt, 1)]);
+}
+
+#[test]
+fn invalid_wave_files_are_rejected() {
+    for ron in [
+        "(enemies: 6, spawn_interval: 1.5",
+        "(enemies: 0, spawn_interval: 1.5, formations: 2, speed_multiplier: 1.2)",
+        "(enemies: 6, spawn_interval: 0.0, formations: 2, speed_multiplier: 1.2)",
+        "(enemies: 6, spawn_interval: 1.5, formations: 2, speed_multiplier: 1.2, kinds: [(Tank, 0)])",
+        "(enemies: 6, spawn_interval: 1.5, formations: 2, speed_multiplier: 1.2, formation: (radius_x: (150.0, 80.0)))",
+        "(enemies: 6, spawn_interval: 1.5, formations: 2, speed_multiplier: 1.2, formation: (pivot_span: (-0.1, 0.4)))",
+    ] {
+        assert!(WaveConfig::from_ron(ron.as_bytes()).is_err(), "{ron}");
+    }
+}
+
+#[test]
+fn scaling_is_capped() {
+    let config = wave(4).scaled(100);
+    assert_eq!(curve(&config), (40, 0.5, 51, 2.));
+    assert_eq!(curve(&WaveConfig::for_wave(1)), curve(&WaveConfig::default()));
+}
+
+#[test]
+fn missing_wave_files_fall_back_to_the_closest_earlier_wave() {
+    let mut app = app();
+    let mut assets = app.world.resource_mut::<Assets<WaveConfig>>();
+    let missing = Handle::weak(HandleId::random::<WaveConfig>());
+    let definitions = WaveDefinitions(vec![assets.add(wave(4)), missing, assets.add(wave(10))]);
+
+    assert_eq!(curve(&definitions.config(1, &assets)), curve(&wave(4)));
+    // the missing second wave doesn't shift the third one
+    assert_eq!(curve(&definitions.config(2, &assets)), curve(&wave(4).scaled(1)));
+    assert_eq!(curve(&definitions.config(3, &assets)), curve(&wave(10)));
+    // past the last file, the last wave gets harder
+    assert_eq!(curve(&definitions.config(5, &assets)), curve(&wave(10).scaled(2)));
+}
+
+#[test]
+fn no_wave_files_use_the_built_in_curve() {
+    let app = app();
+    let assets = app.world.resource::<Assets<WaveConfig>>();
+    let definitions = WaveDefinitions::default();
+    for number in [1, 2, 7] {
+        assert_eq!(curve(&definitions.config(number, assets)), curve(&WaveConfig::for_wave(number)));
+    }
+}